@@ -1,45 +1,270 @@
 use crate::{
     cli,
+    entities::{
+        account,
+        filter::Filter,
+        goal::Goal,
+        move_::{self, Leg},
+    },
     error::{Error, Result},
-    events::{self, Event},
+    events::{self, Event, Events},
     reports::Report,
 };
 
 pub(crate) struct Actions {
-    pub(crate) event: Option<Event>,
+    pub(crate) events: Vec<Event>,
     pub(crate) report: Option<Report>,
 }
 
-pub(crate) fn interpret(args: cli::Arguments) -> Result<Actions> {
-    match args.category {
-        cli::Category::Account(cli::Account::Create(cli::AccountCreate { kind, name })) => {
+pub(crate) fn interpret(args: cli::Arguments, events: &Events) -> Result<Actions> {
+    let actions = interpret_category(args.category, events)?;
+    // appending to a prefix of history would discard the events after it
+    if (args.at_event.is_some() || args.at_time.is_some()) && !actions.events.is_empty() {
+        return Err(Error::ArgumentsInterpreterPastWithNewEvents);
+    }
+    Ok(actions)
+}
+
+fn interpret_category(category: cli::Category, events: &Events) -> Result<Actions> {
+    match category {
+        cli::Category::Account(cli::Account::Create(cli::AccountCreate {
+            kind,
+            name,
+            metadata,
+        })) => Ok(Actions {
+            events: vec![Event::AccountCreated(events::AccountCreated {
+                name,
+                kind,
+                metadata: metadata.into(),
+            })],
+            report: None,
+        }),
+        cli::Category::Account(cli::Account::SetMeta(cli::AccountSetMeta {
+            name,
+            metadata,
+            clear,
+        })) => {
+            let current = events
+                .get_account(&name)
+                .ok_or_else(|| {
+                    Error::ArgumentsInterpreterAccountSetMetaAccountNotFound(name.clone())
+                })?
+                .metadata;
+            let cli::AccountMetadata {
+                opened,
+                institution,
+                number_suffix,
+                notes,
+            } = metadata;
+            let mut metadata = account::Metadata {
+                opened: opened.or(current.opened),
+                institution: institution.clone().or(current.institution),
+                number_suffix: number_suffix.clone().or(current.number_suffix),
+                notes: notes.clone().or(current.notes),
+            };
+            for field in clear {
+                let set = match field {
+                    account::MetadataField::Opened => opened.is_some(),
+                    account::MetadataField::Institution => institution.is_some(),
+                    account::MetadataField::NumberSuffix => number_suffix.is_some(),
+                    account::MetadataField::Notes => notes.is_some(),
+                };
+                if set {
+                    return Err(Error::ArgumentsInterpreterAccountSetMetaSetAndCleared(
+                        field,
+                    ));
+                }
+                match field {
+                    account::MetadataField::Opened => metadata.opened = None,
+                    account::MetadataField::Institution => metadata.institution = None,
+                    account::MetadataField::NumberSuffix => metadata.number_suffix = None,
+                    account::MetadataField::Notes => metadata.notes = None,
+                }
+            }
+            Ok(Actions {
+                events: vec![Event::AccountMetadataSet(events::AccountMetadataSet {
+                    name,
+                    metadata,
+                })],
+                report: None,
+            })
+        }
+        cli::Category::Account(cli::Account::Close(cli::AccountClose { name, date })) => {
             Ok(Actions {
-                event: Some(Event::AccountCreated(events::AccountCreated { name, kind })),
+                events: vec![Event::AccountClosed(events::AccountClosed {
+                    name,
+                    date: date.unwrap_or_else(|| chrono::Local::now().date_naive()),
+                })],
                 report: None,
             })
         }
-        cli::Category::Transaction(cli::Transaction::Record(cli::TransactionRecord { date })) => {
+        cli::Category::Account(cli::Account::List) => Ok(Actions {
+            events: vec![],
+            report: Some(Report::AccountList),
+        }),
+        cli::Category::Account(cli::Account::Show(cli::AccountShow { name, recent })) => {
             Ok(Actions {
-                event: Some(Event::TransactionRecorded(events::TransactionRecorded {
+                events: vec![],
+                report: Some(Report::AccountShow { name, recent }),
+            })
+        }
+        cli::Category::Account(cli::Account::Rename(cli::AccountRename { from, to })) => {
+            if from == to {
+                return Err(Error::ArgumentsInterpreterAccountRenameSameName(from));
+            }
+            Ok(Actions {
+                events: vec![Event::AccountRenamed(events::AccountRenamed { from, to })],
+                report: None,
+            })
+        }
+        cli::Category::Transaction(cli::Transaction::Record(cli::TransactionRecord {
+            date,
+            description,
+            payee,
+            moves,
+        })) => {
+            let transaction = events.next_transaction_id();
+            let moves = moves
+                .into_iter()
+                .map(
+                    |Leg {
+                         debit_account,
+                         credit_account,
+                         amount,
+                         unit,
+                     }| {
+                        if debit_account == credit_account {
+                            return Err(Error::ArgumentsInterpreterMoveAddSameAccount(
+                                debit_account,
+                            ));
+                        }
+                        Ok(Event::MoveAdded(events::MoveAdded {
+                            transaction,
+                            debit_account,
+                            credit_account,
+                            amount,
+                            unit,
+                            memo: None,
+                        }))
+                    },
+                )
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Actions {
+                events: [Event::TransactionRecorded(events::TransactionRecorded {
                     date,
-                })),
+                    description,
+                    payee,
+                })]
+                .into_iter()
+                .chain(moves)
+                .collect(),
                 report: Some(Report::TransactionRecordResponse),
             })
         }
         cli::Category::Transaction(cli::Transaction::Show(cli::TransactionShow { id })) => {
             Ok(Actions {
-                event: None,
+                events: vec![],
                 report: Some(Report::TransactionShow { id }),
             })
         }
+        cli::Category::Transaction(cli::Transaction::Tag(cli::TransactionTag { id, tag })) => {
+            Ok(Actions {
+                events: vec![Event::TransactionTagged(events::TransactionTagged {
+                    transaction: id,
+                    tag,
+                })],
+                report: None,
+            })
+        }
+        cli::Category::Transaction(cli::Transaction::Untag(cli::TransactionTag { id, tag })) => {
+            Ok(Actions {
+                events: vec![Event::TransactionUntagged(events::TransactionUntagged {
+                    transaction: id,
+                    tag,
+                })],
+                report: None,
+            })
+        }
+        cli::Category::Transaction(cli::Transaction::List(cli::TransactionList {
+            filter,
+            account,
+            unit,
+            min_amount,
+            max_amount,
+        })) => Ok(Actions {
+            events: vec![],
+            report: Some(Report::TransactionList {
+                filter: Filter {
+                    account,
+                    unit,
+                    min_amount,
+                    max_amount,
+                    ..filter.into()
+                },
+            }),
+        }),
+        cli::Category::Transaction(cli::Transaction::Void(cli::TransactionVoid { id })) => {
+            Ok(Actions {
+                events: vec![Event::TransactionVoided(events::TransactionVoided {
+                    transaction: id,
+                })],
+                report: None,
+            })
+        }
+        cli::Category::Transaction(cli::Transaction::Reverse(cli::TransactionReverse {
+            id,
+            date,
+        })) => {
+            let reversed = events
+                .get_transaction(&id)
+                .ok_or(Error::ArgumentsInterpreterTransactionReverseTransactionNotFound(id))?;
+            if reversed.voided {
+                return Err(Error::ArgumentsInterpreterTransactionReverseTransactionVoided(id));
+            }
+            let transaction = events.next_transaction_id();
+            let moves = events
+                .all_moves()
+                .filter(|move_| move_.transaction == id)
+                .map(|move_| {
+                    Event::MoveAdded(events::MoveAdded {
+                        transaction,
+                        debit_account: move_.credit_account,
+                        credit_account: move_.debit_account,
+                        amount: move_.amount,
+                        unit: move_.unit,
+                        memo: move_.memo,
+                    })
+                });
+            Ok(Actions {
+                events: [Event::TransactionRecorded(events::TransactionRecorded {
+                    date: date.unwrap_or_else(|| chrono::Local::now().date_naive()),
+                    description: Some(format!("reversal of {id}")),
+                    payee: reversed.payee,
+                })]
+                .into_iter()
+                .chain(moves)
+                .collect(),
+                report: Some(Report::TransactionRecordResponse),
+            })
+        }
+        cli::Category::Transaction(cli::Transaction::SetDate(cli::TransactionSetDate {
+            id,
+            date,
+        })) => Ok(Actions {
+            events: vec![Event::TransactionDateSet(events::TransactionDateSet {
+                transaction: id,
+                date,
+            })],
+            report: None,
+        }),
         cli::Category::Unit(cli::Unit::Create(cli::UnitCreate {
             name,
             decimal_places,
         })) => Ok(Actions {
-            event: Some(Event::UnitCreated(events::UnitCreated {
+            events: vec![Event::UnitCreated(events::UnitCreated {
                 name,
                 decimal_places,
-            })),
+            })],
             report: None,
         }),
         cli::Category::Move(cli::Move::Add(cli::MoveAdd {
@@ -48,28 +273,288 @@ pub(crate) fn interpret(args: cli::Arguments) -> Result<Actions> {
             credit_account,
             amount,
             unit,
+            memo,
         })) => {
             if debit_account == credit_account {
                 return Err(Error::ArgumentsInterpreterMoveAddSameAccount(debit_account));
             }
             Ok(Actions {
-                event: Some(Event::MoveAdded(events::MoveAdded {
+                events: vec![Event::MoveAdded(events::MoveAdded {
                     transaction,
                     debit_account,
                     credit_account,
                     amount,
                     unit,
-                })),
+                    memo,
+                })],
+                report: Some(Report::MoveAddResponse),
+            })
+        }
+        cli::Category::Move(cli::Move::Edit(cli::MoveEdit {
+            id,
+            debit_account,
+            credit_account,
+            amount,
+            unit,
+            memo,
+        })) => {
+            let move_ = events
+                .get_move(&id)
+                .ok_or(Error::ArgumentsInterpreterMoveEditMoveNotFound(id))?;
+            let debit_account = debit_account.unwrap_or(move_.debit_account);
+            let credit_account = credit_account.unwrap_or(move_.credit_account);
+            if debit_account == credit_account {
+                return Err(Error::ArgumentsInterpreterMoveEditSameAccount(
+                    debit_account,
+                ));
+            }
+            Ok(Actions {
+                events: vec![Event::MoveEdited(events::MoveEdited {
+                    move_: id,
+                    debit_account,
+                    credit_account,
+                    amount: amount.unwrap_or(move_.amount),
+                    unit: unit.unwrap_or(move_.unit),
+                    memo: memo.or(move_.memo),
+                })],
                 report: None,
             })
         }
-        cli::Category::Balances => Ok(Actions {
-            event: None,
-            report: Some(Report::Balances),
+        cli::Category::Move(cli::Move::Remove(cli::MoveRemove { id })) => Ok(Actions {
+            events: vec![Event::MoveRemoved(events::MoveRemoved { move_: id })],
+            report: None,
         }),
-        cli::Category::RunningBalance(cli::RunningBalance { account, unit }) => Ok(Actions {
-            event: None,
-            report: Some(Report::RunningBalance { account, unit }),
+        cli::Category::Move(cli::Move::Show(cli::MoveShow { id })) => Ok(Actions {
+            events: vec![],
+            report: Some(Report::MoveShow { id }),
+        }),
+        cli::Category::Budget(cli::Budget::Allocate(cli::BudgetAllocate {
+            envelope,
+            amount,
+            unit,
+            month,
+        })) => Ok(Actions {
+            events: vec![Event::FundsAllocated(events::FundsAllocated {
+                envelope,
+                amount,
+                unit,
+                month,
+            })],
+            report: None,
         }),
+        cli::Category::Budget(cli::Budget::Reallocate(cli::BudgetReallocate {
+            from,
+            to,
+            amount,
+            unit,
+            month,
+        })) => {
+            if from == to {
+                return Err(Error::ArgumentsInterpreterBudgetReallocateSameEnvelope(
+                    from,
+                ));
+            }
+            Ok(Actions {
+                events: vec![Event::FundsReallocated(events::FundsReallocated {
+                    from_envelope: from,
+                    to_envelope: to,
+                    amount,
+                    unit,
+                    month,
+                })],
+                report: None,
+            })
+        }
+        cli::Category::Budget(cli::Budget::Pool) => Ok(Actions {
+            events: vec![],
+            report: Some(Report::ToBeBudgeted),
+        }),
+        cli::Category::Budget(cli::Budget::VsActual(cli::BudgetVsActual { month })) => {
+            Ok(Actions {
+                events: vec![],
+                report: Some(Report::BudgetVsActual { month }),
+            })
+        }
+        cli::Category::Budget(cli::Budget::SetRollover(cli::BudgetSetRollover {
+            envelope,
+            policy,
+        })) => Ok(Actions {
+            events: vec![Event::RolloverPolicySet(events::RolloverPolicySet {
+                envelope,
+                policy,
+            })],
+            report: None,
+        }),
+        cli::Category::Budget(cli::Budget::SetGoal(cli::BudgetSetGoal {
+            envelope,
+            amount,
+            unit,
+            by,
+        })) => {
+            let goal = match by {
+                Some(by) => Goal::TargetBalance { amount, unit, by },
+                None => Goal::MonthlyContribution { amount, unit },
+            };
+            Ok(Actions {
+                events: vec![Event::GoalSet(events::GoalSet { envelope, goal })],
+                report: None,
+            })
+        }
+        cli::Category::Budget(cli::Budget::Goals) => Ok(Actions {
+            events: vec![],
+            report: Some(Report::Goals),
+        }),
+        cli::Category::Budget(cli::Budget::SetOverspending(cli::BudgetSetOverspending {
+            policy,
+        })) => Ok(Actions {
+            events: vec![Event::OverspendingPolicySet(
+                events::OverspendingPolicySet { policy },
+            )],
+            report: None,
+        }),
+        cli::Category::Balances(cli::Balances {
+            filter,
+            as_of,
+            include_closed,
+            by_kind,
+        }) => {
+            let filter = Filter {
+                until: as_of.or(filter.until),
+                ..filter.into()
+            };
+            Ok(Actions {
+                events: vec![],
+                report: Some(if by_kind {
+                    Report::BalancesByKind {
+                        filter,
+                        include_closed,
+                    }
+                } else {
+                    Report::Balances {
+                        filter,
+                        include_closed,
+                    }
+                }),
+            })
+        }
+        cli::Category::Assert(cli::Assert {
+            account,
+            date,
+            amount,
+            unit,
+        }) => Ok(Actions {
+            events: vec![Event::BalanceAsserted(events::BalanceAsserted {
+                account,
+                date,
+                amount,
+                unit,
+            })],
+            report: None,
+        }),
+        cli::Category::Reconcile(cli::Reconcile {
+            account,
+            statement_date,
+            statement_balance,
+            unit,
+            clear,
+        }) => {
+            let mut new_events = clear
+                .into_iter()
+                .map(|move_| {
+                    Event::MoveCleared(events::MoveCleared {
+                        move_,
+                        account: account.clone(),
+                        statement_date,
+                    })
+                })
+                .collect::<Vec<_>>();
+            let mut cleared = events.prefix(events.len());
+            for event in new_events.clone() {
+                cleared.try_push(event.into())?;
+            }
+            // without anything left to reconcile, reconciling is only a report
+            let transactions = cleared.all_transactions();
+            let unreconciled = cleared.all_moves().any(|move_| {
+                move_.status(&account) == move_::Status::Cleared
+                    && move_.unit == unit
+                    && transactions[&move_.transaction].date <= statement_date
+            });
+            if unreconciled
+                && cleared.cleared_balance(&account, &unit, statement_date) == statement_balance
+            {
+                new_events.push(Event::Reconciled(events::Reconciled {
+                    account: account.clone(),
+                    statement_date,
+                    statement_balance,
+                    unit: unit.clone(),
+                }));
+            }
+            Ok(Actions {
+                events: new_events,
+                report: Some(Report::Reconcile {
+                    account,
+                    statement_date,
+                    statement_balance,
+                    unit,
+                }),
+            })
+        }
+        cli::Category::History(cli::History {
+            types,
+            since,
+            until,
+        }) => Ok(Actions {
+            events: vec![],
+            report: Some(Report::History {
+                types,
+                since,
+                until,
+            }),
+        }),
+        cli::Category::Check(cli::Check { strict }) => Ok(Actions {
+            events: vec![],
+            report: Some(Report::Check { strict }),
+        }),
+        cli::Category::RunningBalance(cli::RunningBalance {
+            account,
+            unit,
+            filter,
+        }) => Ok(Actions {
+            events: vec![],
+            report: Some(Report::RunningBalance {
+                account,
+                unit,
+                filter: filter.into(),
+            }),
+        }),
+    }
+}
+
+impl From<cli::Filter> for Filter {
+    fn from(cli::Filter { tags, since, until }: cli::Filter) -> Self {
+        Self {
+            tags,
+            since,
+            until,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<cli::AccountMetadata> for account::Metadata {
+    fn from(
+        cli::AccountMetadata {
+            opened,
+            institution,
+            number_suffix,
+            notes,
+        }: cli::AccountMetadata,
+    ) -> Self {
+        Self {
+            opened,
+            institution,
+            number_suffix,
+            notes,
+        }
     }
 }
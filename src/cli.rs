@@ -1,10 +1,25 @@
-use crate::entities::{account, amount::NonNegativeAmount, transaction, unit};
-use chrono::NaiveDate;
+use crate::{
+    entities::{
+        account,
+        amount::{Amount, NonNegativeAmount},
+        month::Month,
+        move_, overspending, rollover, tag, transaction, unit,
+    },
+    events::Event,
+};
+use chrono::{NaiveDate, NaiveDateTime};
 
 #[derive(clap::Parser)]
 pub(crate) struct Arguments {
     #[clap(subcommand)]
     pub(crate) category: Category,
+    /// Run the command against only the first this many events
+    #[clap(long, global = true)]
+    pub(crate) at_event: Option<usize>,
+    /// Run the command against only the events recorded at or before this local time,
+    /// as `YYYY-MM-DDTHH:MM:SS`
+    #[clap(long, global = true, conflicts_with = "at-event")]
+    pub(crate) at_time: Option<NaiveDateTime>,
 }
 
 #[derive(clap::Subcommand)]
@@ -17,13 +32,41 @@ pub(crate) enum Category {
     Unit(Unit),
     #[clap(subcommand)]
     Move(Move),
-    Balances,
+    #[clap(subcommand)]
+    Budget(Budget),
+    Balances(Balances),
     RunningBalance(RunningBalance),
+    /// Look for transactions that are likely mistakes
+    Check(Check),
+    /// Assert the balance of an account as of the end of a date
+    Assert(Assert),
+    /// Match moves of an account against a statement
+    Reconcile(Reconcile),
+    /// Show the recorded events, oldest first
+    History(History),
+}
+
+#[derive(clap::Args)]
+pub(crate) struct Filter {
+    /// Only count transactions with this tag
+    #[clap(long = "tag")]
+    pub(crate) tags: Vec<tag::Name>,
+    /// Only count transactions dated on or after this date
+    #[clap(long)]
+    pub(crate) since: Option<NaiveDate>,
+    /// Only count transactions dated on or before this date
+    #[clap(long)]
+    pub(crate) until: Option<NaiveDate>,
 }
 
 #[derive(clap::Subcommand)]
 pub(crate) enum Account {
     Create(AccountCreate),
+    Rename(AccountRename),
+    Close(AccountClose),
+    List,
+    Show(AccountShow),
+    SetMeta(AccountSetMeta),
 }
 
 #[derive(clap::Args)]
@@ -33,18 +76,86 @@ pub(crate) struct AccountCreate {
 
     #[clap(long)]
     pub(crate) name: account::Name,
+
+    #[clap(flatten)]
+    pub(crate) metadata: AccountMetadata,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct AccountMetadata {
+    /// No moves are allowed in the account before this date
+    #[clap(long)]
+    pub(crate) opened: Option<NaiveDate>,
+    #[clap(long)]
+    pub(crate) institution: Option<String>,
+    /// The last few digits of the account number
+    #[clap(long)]
+    pub(crate) number_suffix: Option<String>,
+    #[clap(long)]
+    pub(crate) notes: Option<String>,
+}
+
+/// Set details of an account; details that are not given keep their value
+#[derive(clap::Args)]
+pub(crate) struct AccountSetMeta {
+    #[clap(long)]
+    pub(crate) name: account::Name,
+    #[clap(flatten)]
+    pub(crate) metadata: AccountMetadata,
+    /// Details to remove
+    #[clap(long, arg_enum)]
+    pub(crate) clear: Vec<account::MetadataField>,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct AccountShow {
+    #[clap(long)]
+    pub(crate) name: account::Name,
+    /// How many of the latest moves to show
+    #[clap(long, default_value = "5")]
+    pub(crate) recent: usize,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct AccountClose {
+    #[clap(long)]
+    pub(crate) name: account::Name,
+    /// The last date with moves in the account; defaults to today
+    #[clap(long)]
+    pub(crate) date: Option<NaiveDate>,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct AccountRename {
+    #[clap(long)]
+    pub(crate) from: account::Name,
+    #[clap(long)]
+    pub(crate) to: account::Name,
 }
 
 #[derive(clap::Subcommand)]
 pub(crate) enum Transaction {
     Record(TransactionRecord),
     Show(TransactionShow),
+    Tag(TransactionTag),
+    Untag(TransactionTag),
+    List(TransactionList),
+    Void(TransactionVoid),
+    Reverse(TransactionReverse),
+    SetDate(TransactionSetDate),
 }
 
 #[derive(clap::Args)]
 pub(crate) struct TransactionRecord {
     #[clap(long)]
     pub(crate) date: NaiveDate,
+    #[clap(long)]
+    pub(crate) description: Option<String>,
+    #[clap(long)]
+    pub(crate) payee: Option<String>,
+    /// A move to add to the transaction, as `DEBIT->CREDIT AMOUNT UNIT`
+    #[clap(long = "move")]
+    pub(crate) moves: Vec<move_::Leg>,
 }
 
 #[derive(clap::Args)]
@@ -53,6 +164,53 @@ pub(crate) struct TransactionShow {
     pub(crate) id: transaction::Id,
 }
 
+#[derive(clap::Args)]
+pub(crate) struct TransactionVoid {
+    #[clap(long)]
+    pub(crate) id: transaction::Id,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct TransactionReverse {
+    #[clap(long)]
+    pub(crate) id: transaction::Id,
+    /// Date of the reversing transaction; defaults to today
+    #[clap(long)]
+    pub(crate) date: Option<NaiveDate>,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct TransactionSetDate {
+    #[clap(long)]
+    pub(crate) id: transaction::Id,
+    #[clap(long)]
+    pub(crate) date: NaiveDate,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct TransactionTag {
+    #[clap(long)]
+    pub(crate) id: transaction::Id,
+    #[clap(long)]
+    pub(crate) tag: tag::Name,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct TransactionList {
+    #[clap(flatten)]
+    pub(crate) filter: Filter,
+    /// Only list moves from or to this account
+    #[clap(long)]
+    pub(crate) account: Option<account::Name>,
+    /// Only list moves in this unit
+    #[clap(long)]
+    pub(crate) unit: Option<unit::Name>,
+    #[clap(long)]
+    pub(crate) min_amount: Option<NonNegativeAmount>,
+    #[clap(long)]
+    pub(crate) max_amount: Option<NonNegativeAmount>,
+}
+
 #[derive(clap::Subcommand)]
 pub(crate) enum Unit {
     Create(UnitCreate),
@@ -69,6 +227,70 @@ pub(crate) struct UnitCreate {
 #[derive(clap::Subcommand)]
 pub(crate) enum Move {
     Add(MoveAdd),
+    Edit(MoveEdit),
+    Remove(MoveRemove),
+    Show(MoveShow),
+}
+
+#[derive(clap::Args)]
+pub(crate) struct Assert {
+    #[clap(long)]
+    pub(crate) account: account::Name,
+    #[clap(long)]
+    pub(crate) date: NaiveDate,
+    #[clap(long, allow_hyphen_values = true)]
+    pub(crate) amount: Amount,
+    #[clap(long)]
+    pub(crate) unit: unit::Name,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct Reconcile {
+    #[clap(long)]
+    pub(crate) account: account::Name,
+    #[clap(long)]
+    pub(crate) statement_date: NaiveDate,
+    #[clap(long, allow_hyphen_values = true)]
+    pub(crate) statement_balance: Amount,
+    #[clap(long)]
+    pub(crate) unit: unit::Name,
+    /// Mark a move as seen on the statement
+    #[clap(long = "clear")]
+    pub(crate) clear: Vec<move_::Id>,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct History {
+    /// Only show events of this type, such as `MoveAdded`
+    #[clap(long = "type", value_parser = clap::builder::PossibleValuesParser::new(Event::TYPE_NAMES))]
+    pub(crate) types: Vec<String>,
+    /// Only show events recorded at or after this local time, as `YYYY-MM-DDTHH:MM:SS`
+    #[clap(long)]
+    pub(crate) since: Option<NaiveDateTime>,
+    /// Only show events recorded at or before this local time, as `YYYY-MM-DDTHH:MM:SS`
+    #[clap(long)]
+    pub(crate) until: Option<NaiveDateTime>,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct Check {
+    /// Fail if anything is found
+    #[clap(long)]
+    pub(crate) strict: bool,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct Balances {
+    #[clap(flatten)]
+    pub(crate) filter: Filter,
+    /// Balances as of the end of this date; the same as `--until`
+    #[clap(long, conflicts_with = "until")]
+    pub(crate) as_of: Option<NaiveDate>,
+    #[clap(long)]
+    pub(crate) include_closed: bool,
+    /// Group accounts by kind, with totals and net worth
+    #[clap(long)]
+    pub(crate) by_kind: bool,
 }
 
 #[derive(clap::Args)]
@@ -77,6 +299,8 @@ pub(crate) struct RunningBalance {
     pub(crate) account: account::Name,
     #[clap(long)]
     pub(crate) unit: unit::Name,
+    #[clap(flatten)]
+    pub(crate) filter: Filter,
 }
 
 #[derive(clap::Args)]
@@ -91,6 +315,108 @@ pub(crate) struct MoveAdd {
     pub(crate) amount: NonNegativeAmount,
     #[clap(long)]
     pub(crate) unit: unit::Name,
+    #[clap(long)]
+    pub(crate) memo: Option<String>,
+}
+
+/// Correct a move; fields that are not given keep their value
+#[derive(clap::Args)]
+pub(crate) struct MoveEdit {
+    #[clap(long)]
+    pub(crate) id: move_::Id,
+    #[clap(long)]
+    pub(crate) debit_account: Option<account::Name>,
+    #[clap(long)]
+    pub(crate) credit_account: Option<account::Name>,
+    #[clap(long)]
+    pub(crate) amount: Option<NonNegativeAmount>,
+    #[clap(long)]
+    pub(crate) unit: Option<unit::Name>,
+    #[clap(long)]
+    pub(crate) memo: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct MoveRemove {
+    #[clap(long)]
+    pub(crate) id: move_::Id,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct MoveShow {
+    #[clap(long)]
+    pub(crate) id: move_::Id,
+}
+
+#[derive(clap::Subcommand)]
+pub(crate) enum Budget {
+    Allocate(BudgetAllocate),
+    Reallocate(BudgetReallocate),
+    Pool,
+    VsActual(BudgetVsActual),
+    SetRollover(BudgetSetRollover),
+    SetGoal(BudgetSetGoal),
+    Goals,
+    SetOverspending(BudgetSetOverspending),
+}
+
+#[derive(clap::Args)]
+pub(crate) struct BudgetAllocate {
+    #[clap(long)]
+    pub(crate) envelope: account::Name,
+    #[clap(long)]
+    pub(crate) amount: NonNegativeAmount,
+    #[clap(long)]
+    pub(crate) unit: unit::Name,
+    #[clap(long)]
+    pub(crate) month: Month,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct BudgetReallocate {
+    #[clap(long)]
+    pub(crate) from: account::Name,
+    #[clap(long)]
+    pub(crate) to: account::Name,
+    #[clap(long)]
+    pub(crate) amount: NonNegativeAmount,
+    #[clap(long)]
+    pub(crate) unit: unit::Name,
+    #[clap(long)]
+    pub(crate) month: Month,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct BudgetVsActual {
+    #[clap(long)]
+    pub(crate) month: Month,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct BudgetSetRollover {
+    #[clap(long)]
+    pub(crate) envelope: account::Name,
+    #[clap(long, arg_enum)]
+    pub(crate) policy: rollover::Policy,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct BudgetSetGoal {
+    #[clap(long)]
+    pub(crate) envelope: account::Name,
+    #[clap(long)]
+    pub(crate) amount: NonNegativeAmount,
+    #[clap(long)]
+    pub(crate) unit: unit::Name,
+    /// Reach the amount by this date, rather than contribute it monthly
+    #[clap(long)]
+    pub(crate) by: Option<NaiveDate>,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct BudgetSetOverspending {
+    #[clap(long, arg_enum)]
+    pub(crate) policy: overspending::Policy,
 }
 
 #[test]
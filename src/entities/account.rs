@@ -1,28 +1,156 @@
-use std::{fmt::Display, str::FromStr};
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
 
+use chrono::NaiveDate;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
+use super::amount::Amount;
 use crate::error::Error;
 
+#[derive(Clone)]
 pub(crate) struct Account {
-    pub(crate) _kind: Kind,
-    pub(crate) _name: Name,
+    pub(crate) kind: Kind,
+    pub(crate) name: Name,
+    /// Accounts are numbered in the order they were created, starting at 1
+    pub(crate) order: usize,
+    pub(crate) closed: Option<NaiveDate>,
+    pub(crate) metadata: Metadata,
 }
 
-#[derive(Clone, Copy, ValueEnum, Serialize, Deserialize, Debug, PartialEq, Eq)]
+impl Account {
+    /// Whether the account was closed by the end of the date,
+    /// or by now if there is no date
+    pub(crate) fn closed_as_of(&self, date: Option<NaiveDate>) -> bool {
+        self.closed
+            .is_some_and(|closed| date.is_none_or(|date| date >= closed))
+    }
+}
+
+/// Optional details about an account
+#[derive(Clone, Default, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub(crate) struct Metadata {
+    /// No moves are allowed in the account before the date
+    #[serde(default)]
+    pub(crate) opened: Option<NaiveDate>,
+    #[serde(default)]
+    pub(crate) institution: Option<String>,
+    /// The last few digits of the account number, as printed on statements
+    #[serde(default)]
+    pub(crate) number_suffix: Option<String>,
+    #[serde(default)]
+    pub(crate) notes: Option<String>,
+}
+
+/// One of the optional details about an account
+#[derive(Clone, Copy, ValueEnum, Debug, PartialEq, Eq)]
+pub(crate) enum MetadataField {
+    Opened,
+    Institution,
+    NumberSuffix,
+    Notes,
+}
+
+#[derive(Clone, Copy, ValueEnum, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) enum Kind {
-    External,
+    /// An envelope of the budget
     Budget,
+    Asset,
+    /// Such as a credit card or a loan
+    Liability,
+    Income,
+    Expense,
+    Equity,
+    /// Outside of the budget and not classified any further
+    External,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Clone, Debug)]
+impl Kind {
+    /// Liabilities, income and equity normally have negative balances,
+    /// so they are shown negated
+    pub(crate) fn signed(self, amount: Amount) -> Amount {
+        match self {
+            Kind::Liability | Kind::Income | Kind::Equity => -amount,
+            Kind::Budget | Kind::Asset | Kind::Expense | Kind::External => amount,
+        }
+    }
+    pub(crate) fn is_net_worth(self) -> bool {
+        matches!(self, Kind::Budget | Kind::Asset | Kind::Liability)
+    }
+}
+
+/// Segments separated by `:` form a hierarchy, as in `expenses:food:groceries`
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
 pub(crate) struct Name(pub(crate) String);
 
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::Budget => write!(f, "budget"),
+            Kind::Asset => write!(f, "asset"),
+            Kind::Liability => write!(f, "liability"),
+            Kind::Income => write!(f, "income"),
+            Kind::Expense => write!(f, "expense"),
+            Kind::Equity => write!(f, "equity"),
+            Kind::External => write!(f, "external"),
+        }
+    }
+}
+
+impl Name {
+    pub(crate) const SEPARATOR: char = ':';
+
+    pub(crate) fn segments(&self) -> impl Iterator<Item = &str> {
+        self.0.split(Self::SEPARATOR)
+    }
+    /// Every ancestor, from the root down to the parent
+    pub(crate) fn ancestors(&self) -> Vec<Name> {
+        self.0
+            .match_indices(Self::SEPARATOR)
+            .map(|(index, _)| Name(self.0[..index].to_string()))
+            .collect()
+    }
+    pub(crate) fn depth(&self) -> usize {
+        self.segments().count() - 1
+    }
+    pub(crate) fn leaf(&self) -> &str {
+        self.segments().last().unwrap()
+    }
+    /// Replaces the `from` prefix, if this is `from` or one of its descendants
+    pub(crate) fn rename(&mut self, from: &Name, to: &Name) {
+        if self.is_within(from) {
+            self.0 = format!("{}{}", to.0, &self.0[from.0.len()..]);
+        }
+    }
+    /// Whether this is the other account or one of its descendants
+    pub(crate) fn is_within(&self, other: &Name) -> bool {
+        self == other
+            || self
+                .0
+                .strip_prefix(&other.0)
+                .is_some_and(|rest| rest.starts_with(Self::SEPARATOR))
+    }
+}
+
+/// Children are ordered right after their parent
+impl Ord for Name {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.segments().cmp(other.segments())
+    }
+}
+
+impl PartialOrd for Name {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl FromStr for Name {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.split(Self::SEPARATOR).any(str::is_empty) {
+            return Err(Error::AccountNameEmptySegment(s.into()));
+        }
         Ok(Self(String::from(s)))
     }
 }
@@ -32,3 +160,23 @@ impl Display for Name {
         self.0.fmt(f)
     }
 }
+
+#[test]
+fn hierarchy() {
+    let name = |s: &str| s.parse::<Name>().unwrap();
+    assert!(name("a:b") < name("a b"));
+    assert!(name("a:b:c").is_within(&name("a")));
+    assert!(!name("ab").is_within(&name("a")));
+    assert_eq!(name("a:b:c").ancestors(), [name("a"), name("a:b")]);
+    assert!("a::b".parse::<Name>().is_err());
+}
+
+#[test]
+fn legacy_kinds() {
+    assert_eq!(ron::from_str::<Kind>("External").unwrap(), Kind::External);
+    assert_eq!(ron::from_str::<Kind>("Budget").unwrap(), Kind::Budget);
+    assert_eq!(
+        Kind::Liability.signed("-3".parse().unwrap()),
+        "3".parse().unwrap()
+    );
+}
@@ -0,0 +1,10 @@
+use chrono::NaiveDate;
+
+use super::{account, amount::Amount, unit};
+
+pub(crate) struct Activity {
+    pub(crate) date: NaiveDate,
+    pub(crate) envelope: account::Name,
+    pub(crate) amount: Amount,
+    pub(crate) unit: unit::Name,
+}
@@ -0,0 +1,8 @@
+use super::{account, amount::Amount, month::Month, unit};
+
+pub(crate) struct Allocation {
+    pub(crate) envelope: account::Name,
+    pub(crate) amount: Amount,
+    pub(crate) unit: unit::Name,
+    pub(crate) month: Month,
+}
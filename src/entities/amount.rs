@@ -4,9 +4,40 @@ use std::{fmt::Display, ops, str::FromStr};
 
 use crate::error::Error;
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub(crate) struct Amount(pub(crate) Decimal);
 
+impl Amount {
+    pub(crate) fn rescaled(mut self, scale: u32) -> Self {
+        self.0.rescale(scale);
+        self
+    }
+}
+
+impl ops::Neg for Amount {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl ops::Add<Self> for Amount {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl ops::Sub<Self> for Amount {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
 impl ops::SubAssign<Self> for Amount {
     fn sub_assign(&mut self, rhs: Self) {
         self.0 -= rhs.0;
@@ -35,7 +66,7 @@ impl FromStr for Amount {
     }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub(crate) struct NonNegativeAmount(Decimal);
 
 impl NonNegativeAmount {
@@ -0,0 +1,18 @@
+use chrono::NaiveDate;
+
+use super::{account, amount::Amount, unit};
+
+/// The balance of an account in a unit, as of the end of a date
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Assertion {
+    pub(crate) account: account::Name,
+    pub(crate) date: NaiveDate,
+    pub(crate) amount: Amount,
+    pub(crate) unit: unit::Name,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Failure {
+    pub(crate) assertion: Assertion,
+    pub(crate) actual: Amount,
+}
@@ -0,0 +1,9 @@
+use super::amount::Amount;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct EnvelopeMonth {
+    pub(crate) carried_over: Amount,
+    pub(crate) allocated: Amount,
+    pub(crate) activity: Amount,
+    pub(crate) available: Amount,
+}
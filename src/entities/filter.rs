@@ -0,0 +1,48 @@
+use chrono::NaiveDate;
+
+use super::{
+    account,
+    amount::{Amount, NonNegativeAmount},
+    move_::Move,
+    tag,
+    transaction::Transaction,
+    unit,
+};
+
+/// Restricts reports to the moves it matches, of the transactions it matches
+#[derive(Debug, Default)]
+pub(crate) struct Filter {
+    pub(crate) tags: Vec<tag::Name>,
+    pub(crate) since: Option<NaiveDate>,
+    pub(crate) until: Option<NaiveDate>,
+    pub(crate) account: Option<account::Name>,
+    pub(crate) unit: Option<unit::Name>,
+    pub(crate) min_amount: Option<NonNegativeAmount>,
+    pub(crate) max_amount: Option<NonNegativeAmount>,
+}
+
+impl Filter {
+    pub(crate) fn matches(&self, transaction: &Transaction) -> bool {
+        self.tags.iter().all(|tag| transaction.tags.contains(tag))
+            && self.since.is_none_or(|since| transaction.date >= since)
+            && self.until.is_none_or(|until| transaction.date <= until)
+    }
+    pub(crate) fn matches_move(&self, move_: &Move) -> bool {
+        let amount = Amount::from(move_.amount);
+        self.account.as_ref().is_none_or(|account| {
+            move_.debit_account.is_within(account) || move_.credit_account.is_within(account)
+        }) && self.unit.as_ref().is_none_or(|unit| &move_.unit == unit)
+            && self
+                .min_amount
+                .is_none_or(|min_amount| amount >= min_amount.into())
+            && self
+                .max_amount
+                .is_none_or(|max_amount| amount <= max_amount.into())
+    }
+    pub(crate) fn is_move_specific(&self) -> bool {
+        self.account.is_some()
+            || self.unit.is_some()
+            || self.min_amount.is_some()
+            || self.max_amount.is_some()
+    }
+}
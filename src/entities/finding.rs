@@ -0,0 +1,30 @@
+use std::fmt::Display;
+
+use super::{transaction, unit};
+
+/// Something about a transaction that is likely a mistake
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Kind {
+    /// The transaction has no moves
+    NoMoves,
+    /// None of the transaction's moves are from or to a budget account
+    NoBudgetAccount,
+    /// The unit is used by a single move, while other moves use other units
+    SingleLegUnit(unit::Name),
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Finding {
+    pub(crate) transaction: transaction::Id,
+    pub(crate) kind: Kind,
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Kind::NoMoves => write!(f, "no moves"),
+            Kind::NoBudgetAccount => write!(f, "no budget account"),
+            Kind::SingleLegUnit(unit) => write!(f, "single move in {unit}"),
+        }
+    }
+}
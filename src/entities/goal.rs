@@ -0,0 +1,42 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    amount::{Amount, NonNegativeAmount},
+    unit,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Goal {
+    TargetBalance {
+        amount: NonNegativeAmount,
+        unit: unit::Name,
+        by: NaiveDate,
+    },
+    MonthlyContribution {
+        amount: NonNegativeAmount,
+        unit: unit::Name,
+    },
+}
+
+impl Goal {
+    pub(crate) fn amount(&self) -> NonNegativeAmount {
+        match self {
+            Goal::TargetBalance { amount, .. } | Goal::MonthlyContribution { amount, .. } => {
+                *amount
+            }
+        }
+    }
+    pub(crate) fn unit(&self) -> &unit::Name {
+        match self {
+            Goal::TargetBalance { unit, .. } | Goal::MonthlyContribution { unit, .. } => unit,
+        }
+    }
+}
+
+pub(crate) struct Progress {
+    pub(crate) goal: Goal,
+    pub(crate) percent: Decimal,
+    pub(crate) needed_monthly: Amount,
+}
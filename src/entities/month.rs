@@ -0,0 +1,53 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub(crate) struct Month {
+    pub(crate) year: i32,
+    pub(crate) month: u32,
+}
+
+impl Month {
+    pub(crate) fn of(date: NaiveDate) -> Self {
+        Self {
+            year: date.year(),
+            month: date.month(),
+        }
+    }
+    pub(crate) fn months_until(&self, other: Month) -> i64 {
+        (other.year - self.year) as i64 * 12 + other.month as i64 - self.month as i64
+    }
+    pub(crate) fn succ(&self) -> Self {
+        if self.month == 12 {
+            Self {
+                year: self.year + 1,
+                month: 1,
+            }
+        } else {
+            Self {
+                year: self.year,
+                month: self.month + 1,
+            }
+        }
+    }
+}
+
+impl Display for Month {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+impl FromStr for Month {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NaiveDate::parse_from_str(&format!("{s}-01"), "%F")
+            .map_err(Error::MonthFailedToParse)
+            .map(Self::of)
+    }
+}
@@ -1,9 +1,106 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
 use super::{account, amount::NonNegativeAmount, transaction, unit};
+use crate::error::Error;
+
+/// Moves are numbered in the order they were added, starting at 1
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug, PartialOrd, Ord)]
+pub(crate) struct Id(pub(crate) u64);
+
+/// How far a move has been matched against account statements
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub(crate) enum Status {
+    #[default]
+    Pending,
+    /// Seen on a statement
+    Cleared,
+    /// Part of a reconciliation whose cleared balance matched the statement
+    Reconciled,
+}
 
 pub(crate) struct Move {
+    pub(crate) id: Id,
     pub(crate) transaction: transaction::Id,
     pub(crate) debit_account: account::Name,
     pub(crate) credit_account: account::Name,
     pub(crate) amount: NonNegativeAmount,
     pub(crate) unit: unit::Name,
+    pub(crate) memo: Option<String>,
+    /// As matched against statements of the debit account
+    pub(crate) debit_status: Status,
+    /// As matched against statements of the credit account
+    pub(crate) credit_status: Status,
+}
+
+impl Move {
+    /// How far the move has been matched against statements of an account it touches
+    pub(crate) fn status(&self, account: &account::Name) -> Status {
+        if &self.debit_account == account {
+            self.debit_status
+        } else if &self.credit_account == account {
+            self.credit_status
+        } else {
+            Status::Pending
+        }
+    }
+    /// Whether the move has been seen on a statement of either of its accounts
+    pub(crate) fn is_cleared(&self) -> bool {
+        self.debit_status != Status::Pending || self.credit_status != Status::Pending
+    }
+}
+
+/// A move of a transaction yet to be recorded, written `DEBIT->CREDIT AMOUNT UNIT`
+#[derive(Debug)]
+pub(crate) struct Leg {
+    pub(crate) debit_account: account::Name,
+    pub(crate) credit_account: account::Name,
+    pub(crate) amount: NonNegativeAmount,
+    pub(crate) unit: unit::Name,
+}
+
+impl FromStr for Leg {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let failed = || Error::LegFailedToParse(s.into());
+        let (debit_account, rest) = s.split_once("->").ok_or_else(failed)?;
+        let mut words = rest.trim().rsplitn(3, ' ');
+        let (Some(unit), Some(amount), Some(credit_account)) =
+            (words.next(), words.next(), words.next())
+        else {
+            return Err(failed());
+        };
+        Ok(Self {
+            debit_account: debit_account.trim().parse()?,
+            credit_account: credit_account.trim().parse()?,
+            amount: amount.parse()?,
+            unit: unit.parse()?,
+        })
+    }
+}
+
+impl Display for Id {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pending => write!(f, "pending"),
+            Status::Cleared => write!(f, "cleared"),
+            Status::Reconciled => write!(f, "reconciled"),
+        }
+    }
+}
+
+impl FromStr for Id {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map_err(Error::MoveIdFailedToParse).map(Self)
+    }
 }
@@ -0,0 +1,30 @@
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use super::{account, amount::Amount, unit};
+
+#[derive(Clone, Copy, ValueEnum, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub(crate) enum Policy {
+    #[default]
+    Warn,
+    Reject,
+}
+
+impl Display for Policy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Policy::Warn => write!(f, "warn"),
+            Policy::Reject => write!(f, "reject"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Overspending {
+    pub(crate) envelope: account::Name,
+    pub(crate) unit: unit::Name,
+    pub(crate) before: Amount,
+    pub(crate) after: Amount,
+}
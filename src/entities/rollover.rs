@@ -0,0 +1,37 @@
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use super::amount::Amount;
+
+#[derive(Clone, Copy, ValueEnum, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub(crate) enum Policy {
+    /// Carry over whatever is available, including overspending
+    #[default]
+    Everything,
+    /// Carry over leftovers; overspending is covered by the pool
+    Positive,
+    /// Return leftovers and overspending to the pool
+    Reset,
+}
+
+impl Display for Policy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Policy::Everything => write!(f, "everything"),
+            Policy::Positive => write!(f, "positive"),
+            Policy::Reset => write!(f, "reset"),
+        }
+    }
+}
+
+impl Policy {
+    pub(crate) fn carry_over(&self, available: Amount) -> Amount {
+        match self {
+            Policy::Everything => available,
+            Policy::Positive => available.max(Amount::default()),
+            Policy::Reset => Amount::default(),
+        }
+    }
+}
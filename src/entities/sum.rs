@@ -1,4 +0,0 @@
-use serde::{Deserialize, Serialize};
-
-#[derive(Serialize, Deserialize, Default)]
-pub(crate) struct Sum {}
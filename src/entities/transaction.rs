@@ -1,17 +1,32 @@
-use std::{fmt::Display, str::FromStr};
+use std::{collections::BTreeSet, fmt::Display, str::FromStr};
 
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
+use super::tag;
 use crate::error::Error;
 
 #[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug, PartialOrd, Ord)]
 pub(crate) struct Id(pub(crate) u64);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct Transaction {
     pub(crate) id: Id,
     pub(crate) date: NaiveDate,
+    pub(crate) description: Option<String>,
+    pub(crate) payee: Option<String>,
+    pub(crate) tags: BTreeSet<tag::Name>,
+    pub(crate) voided: bool,
+}
+
+impl Transaction {
+    pub(crate) fn summary(&self) -> String {
+        match (&self.payee, &self.description) {
+            (Some(payee), Some(description)) => format!("{payee}: {description}"),
+            (Some(text), None) | (None, Some(text)) => text.clone(),
+            (None, None) => String::new(),
+        }
+    }
 }
 
 impl Display for Id {
@@ -1,4 +1,8 @@
-use crate::entities::{account, transaction, unit};
+use chrono::NaiveDate;
+
+use crate::entities::{
+    account, amount::Amount, assertion, move_, overspending::Overspending, tag, transaction, unit,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub(crate) enum Error {
@@ -8,8 +12,16 @@ pub(crate) enum Error {
     NonNegativeAmountParsedNegativeDecimal,
     #[error("parsing `Amount`: {0}")]
     AmountFailedToParseDecimal(rust_decimal::Error),
+    #[error("parsing `account::Name`: empty segment: {0}")]
+    AccountNameEmptySegment(String),
     #[error("parsing `transaction::Id`: {0}")]
     TransactionIdFailedToParse(std::num::ParseIntError),
+    #[error("parsing `move_::Id`: {0}")]
+    MoveIdFailedToParse(std::num::ParseIntError),
+    #[error("parsing `Leg`: expected `DEBIT->CREDIT AMOUNT UNIT`: {0}")]
+    LegFailedToParse(String),
+    #[error("parsing `Month`: {0}")]
+    MonthFailedToParse(chrono::ParseError),
     #[error("event invalid for appending: {0}")]
     EventValidateForAppendingTo(#[from] EventValidateForAppendingToError),
     #[error("reading serialized events into string: {0}")]
@@ -18,10 +30,36 @@ pub(crate) enum Error {
     EventsFailedToDeserialize(ron::error::SpannedError),
     #[error("serializing events: {0}")]
     EventsFailedToSerialize(ron::Error),
+    #[error("only {len} events to replay, not {at_event}")]
+    EventsAtEventOutOfRange { at_event: usize, len: usize },
     #[error("generating report `TransactionShow`: transaction not found: {0}")]
     ReportTransactionShowTransactionNotFound(transaction::Id),
+    #[error("generating report `Check`: found likely mistakes:\n{0}")]
+    ReportCheckFindings(String),
+    #[error("generating report `AccountShow`: account not found: {0}")]
+    ReportAccountShowAccountNotFound(account::Name),
+    #[error("generating report `MoveShow`: move not found: {0}")]
+    ReportMoveShowMoveNotFound(move_::Id),
+    #[error("invalid arguments: `--at-event` or `--at-time`: the command would record events")]
+    ArgumentsInterpreterPastWithNewEvents,
+    #[error("invalid arguments: `TransactionReverse`: transaction not found: {0}")]
+    ArgumentsInterpreterTransactionReverseTransactionNotFound(transaction::Id),
+    #[error("invalid arguments: `TransactionReverse`: transaction voided: {0}")]
+    ArgumentsInterpreterTransactionReverseTransactionVoided(transaction::Id),
+    #[error("invalid arguments: `AccountSetMeta`: account not found: {0}")]
+    ArgumentsInterpreterAccountSetMetaAccountNotFound(account::Name),
+    #[error("invalid arguments: `AccountSetMeta`: both set and cleared: {0:?}")]
+    ArgumentsInterpreterAccountSetMetaSetAndCleared(account::MetadataField),
+    #[error("invalid arguments: `AccountRename`: same name: {0}")]
+    ArgumentsInterpreterAccountRenameSameName(account::Name),
     #[error("invalid arguments: `MoveAdd`: same account: {0}")]
     ArgumentsInterpreterMoveAddSameAccount(crate::entities::account::Name),
+    #[error("invalid arguments: `MoveEdit`: move not found: {0}")]
+    ArgumentsInterpreterMoveEditMoveNotFound(move_::Id),
+    #[error("invalid arguments: `MoveEdit`: same account: {0}")]
+    ArgumentsInterpreterMoveEditSameAccount(account::Name),
+    #[error("invalid arguments: `BudgetReallocate`: same envelope: {0}")]
+    ArgumentsInterpreterBudgetReallocateSameEnvelope(crate::entities::account::Name),
     #[error("failed to open persistence file: {0}")]
     PersistenceFileOpenFailed(std::io::Error),
     #[error("failed to initialize persistence file: {0}")]
@@ -34,25 +72,181 @@ pub(crate) enum Error {
 #[error("{self:?}")]
 pub(crate) struct EventValidateForAppendingToErrorMoveAdded {
     pub(crate) transaction_not_found: Option<transaction::Id>,
+    pub(crate) transaction_voided: Option<transaction::Id>,
+    pub(crate) account_date: Option<EventValidateForAppendingToErrorAccountDate>,
+    pub(crate) debit_account_not_found: Option<account::Name>,
+    pub(crate) credit_account_not_found: Option<account::Name>,
+    pub(crate) unit: Option<EventValidateForAppendingToErrorUnit>,
+    pub(crate) overspending: Option<Box<Overspending>>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[error("{self:?}")]
+pub(crate) struct EventValidateForAppendingToErrorAccountClosed {
+    pub(crate) account_not_found: Option<account::Name>,
+    pub(crate) already_closed: Option<NaiveDate>,
+    pub(crate) non_zero_balance: Option<(unit::Name, Amount)>,
+    pub(crate) later_move: Option<move_::Id>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[error("{self:?}")]
+pub(crate) struct EventValidateForAppendingToErrorAccountMetadataSet {
+    pub(crate) account_not_found: Option<account::Name>,
+    pub(crate) earlier_move: Option<move_::Id>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[error("{self:?}")]
+pub(crate) struct EventValidateForAppendingToErrorMoveEdited {
+    pub(crate) move_not_found: Option<move_::Id>,
+    pub(crate) move_cleared: Option<move_::Id>,
+    pub(crate) transaction_voided: Option<transaction::Id>,
     pub(crate) debit_account_not_found: Option<account::Name>,
     pub(crate) credit_account_not_found: Option<account::Name>,
-    pub(crate) unit: Option<EventValidateForAppendingToErrorMoveAddedUnit>,
+    pub(crate) account_date: Option<EventValidateForAppendingToErrorAccountDate>,
+    pub(crate) unit: Option<EventValidateForAppendingToErrorUnit>,
+    pub(crate) overspending: Option<Box<Overspending>>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[error("{self:?}")]
+pub(crate) struct EventValidateForAppendingToErrorFundsAllocated {
+    pub(crate) envelope: Option<EventValidateForAppendingToErrorEnvelope>,
+    pub(crate) unit: Option<EventValidateForAppendingToErrorUnit>,
+    pub(crate) insufficient_funds: Option<EventValidateForAppendingToErrorInsufficientFunds>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[error("{self:?}")]
+pub(crate) struct EventValidateForAppendingToErrorFundsReallocated {
+    pub(crate) from_envelope: Option<EventValidateForAppendingToErrorEnvelope>,
+    pub(crate) to_envelope: Option<EventValidateForAppendingToErrorEnvelope>,
+    pub(crate) unit: Option<EventValidateForAppendingToErrorUnit>,
+    pub(crate) insufficient_funds: Option<EventValidateForAppendingToErrorInsufficientFunds>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[error("{self:?}")]
+pub(crate) struct EventValidateForAppendingToErrorBalanceAsserted {
+    pub(crate) account_not_found: Option<account::Name>,
+    pub(crate) unit: Option<EventValidateForAppendingToErrorUnit>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[error("{self:?}")]
+pub(crate) struct EventValidateForAppendingToErrorReconciled {
+    pub(crate) account_not_found: Option<account::Name>,
+    pub(crate) unit: Option<EventValidateForAppendingToErrorUnit>,
+    pub(crate) cleared_balance_mismatch: Option<Amount>,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[error("{self:?}")]
+pub(crate) struct EventValidateForAppendingToErrorGoalSet {
+    pub(crate) envelope: Option<EventValidateForAppendingToErrorEnvelope>,
+    pub(crate) unit: Option<EventValidateForAppendingToErrorUnit>,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub(crate) enum EventValidateForAppendingToErrorMoveAddedUnit {
+pub(crate) enum EventValidateForAppendingToErrorUnit {
     UnitNotFound(unit::Name),
     DecimalPlacesMismatch { unit_scale: u8, amount_scale: u32 },
 }
 
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum EventValidateForAppendingToErrorEnvelope {
+    AccountNotFound(account::Name),
+    AccountNotBudget(account::Name),
+}
+
+/// A move dated when one of its accounts was not open
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum EventValidateForAppendingToErrorAccountDate {
+    AccountClosed(account::Name),
+    AccountNotOpened(account::Name),
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct EventValidateForAppendingToErrorInsufficientFunds {
+    pub(crate) available: Amount,
+    pub(crate) requested: Amount,
+}
+
 #[derive(Debug, thiserror::Error, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) enum EventValidateForAppendingToError {
     #[error("`AccountCreated`: `account::Name` collision: {0}")]
     AccountCreatedNameCollision(account::Name),
+    #[error("`AccountRenamed`: account not found: {0}")]
+    AccountRenamedAccountNotFound(account::Name),
+    #[error("`AccountRenamed`: `account::Name` collision: {0}")]
+    AccountRenamedNameCollision(account::Name),
+    #[error("{0}")]
+    AccountClosed(Box<EventValidateForAppendingToErrorAccountClosed>),
+    #[error("{0}")]
+    AccountMetadataSet(EventValidateForAppendingToErrorAccountMetadataSet),
     #[error("`UnitCreated`: `unit::Name` collision: {0}")]
     UnitCreatedNameCollision(unit::Name),
     #[error("{0}")]
-    MoveAdded(EventValidateForAppendingToErrorMoveAdded),
+    MoveAdded(Box<EventValidateForAppendingToErrorMoveAdded>),
+    #[error("{0}")]
+    MoveEdited(Box<EventValidateForAppendingToErrorMoveEdited>),
+    #[error("`MoveRemoved`: move not found: {0}")]
+    MoveRemovedMoveNotFound(move_::Id),
+    #[error("`MoveRemoved`: move seen on a statement: {0}")]
+    MoveRemovedMoveCleared(move_::Id),
+    #[error("`MoveRemoved`: move {0} of closed account {1}")]
+    MoveRemovedAccountClosed(move_::Id, account::Name),
+    #[error("`MoveRemoved`: removing move {0} overspends: {1:?}")]
+    MoveRemovedOverspending(move_::Id, Box<Overspending>),
+    #[error("{0}")]
+    FundsAllocated(Box<EventValidateForAppendingToErrorFundsAllocated>),
+    #[error("{0}")]
+    FundsReallocated(Box<EventValidateForAppendingToErrorFundsReallocated>),
+    #[error("`RolloverPolicySet`: {0:?}")]
+    RolloverPolicySet(EventValidateForAppendingToErrorEnvelope),
+    #[error("{0}")]
+    GoalSet(EventValidateForAppendingToErrorGoalSet),
+    #[error("`TransactionTagged`: transaction not found: {0}")]
+    TransactionTaggedTransactionNotFound(transaction::Id),
+    #[error("`TransactionTagged`: already tagged: {0}")]
+    TransactionTaggedAlreadyTagged(tag::Name),
+    #[error("`TransactionUntagged`: not tagged: {0}")]
+    TransactionUntaggedNotTagged(tag::Name),
+    #[error("`TransactionVoided`: transaction not found: {0}")]
+    TransactionVoidedTransactionNotFound(transaction::Id),
+    #[error("`TransactionVoided`: already voided: {0}")]
+    TransactionVoidedAlreadyVoided(transaction::Id),
+    #[error("`TransactionVoided`: move seen on a statement: {0}")]
+    TransactionVoidedMoveCleared(move_::Id),
+    #[error("`TransactionVoided`: move {0} of closed account {1}")]
+    TransactionVoidedAccountClosed(move_::Id, account::Name),
+    #[error("{0}")]
+    BalanceAsserted(EventValidateForAppendingToErrorBalanceAsserted),
+    #[error(
+        "balance assertion failed: {} on {}: asserted {} {}, actual {} {}",
+        .0.assertion.account,
+        .0.assertion.date,
+        .0.assertion.amount,
+        .0.assertion.unit,
+        .0.actual,
+        .0.assertion.unit
+    )]
+    BalanceAssertionFailed(Box<assertion::Failure>),
+    #[error("`MoveCleared`: move not found: {0}")]
+    MoveClearedMoveNotFound(move_::Id),
+    #[error("`MoveCleared`: move {0} does not touch account {1}")]
+    MoveClearedAccountNotMoved(move_::Id, account::Name),
+    #[error("`MoveCleared`: move {0} is dated after the statement date {1}")]
+    MoveClearedAfterStatementDate(move_::Id, NaiveDate),
+    #[error("`MoveCleared`: already cleared: {0}")]
+    MoveClearedAlreadyCleared(move_::Id),
+    #[error("{0}")]
+    Reconciled(EventValidateForAppendingToErrorReconciled),
+    #[error("`TransactionDateSet`: transaction not found: {0}")]
+    TransactionDateSetTransactionNotFound(transaction::Id),
+    #[error("`TransactionDateSet`: move {0}: {1:?}")]
+    TransactionDateSetAccountDate(move_::Id, EventValidateForAppendingToErrorAccountDate),
 }
 
 pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
@@ -1,31 +1,68 @@
-use crate::entities::{account, amount::NonNegativeAmount, transaction, unit};
+use crate::entities::{
+    account::{self, Account},
+    amount::{Amount, NonNegativeAmount},
+    goal::Goal,
+    month::Month,
+    move_::{self, Move},
+    overspending, rollover, tag, transaction, unit,
+};
 use crate::error::{
-    Error, EventValidateForAppendingToError, EventValidateForAppendingToErrorMoveAdded,
-    EventValidateForAppendingToErrorMoveAddedUnit, Result,
+    Error, EventValidateForAppendingToError, EventValidateForAppendingToErrorAccountClosed,
+    EventValidateForAppendingToErrorAccountDate,
+    EventValidateForAppendingToErrorAccountMetadataSet,
+    EventValidateForAppendingToErrorBalanceAsserted, EventValidateForAppendingToErrorEnvelope,
+    EventValidateForAppendingToErrorFundsAllocated,
+    EventValidateForAppendingToErrorFundsReallocated, EventValidateForAppendingToErrorGoalSet,
+    EventValidateForAppendingToErrorInsufficientFunds, EventValidateForAppendingToErrorMoveAdded,
+    EventValidateForAppendingToErrorMoveEdited, EventValidateForAppendingToErrorReconciled,
+    EventValidateForAppendingToErrorUnit, Result,
 };
-use chrono::NaiveDate;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
 use itertools::Itertools;
 use readext::ReadExt;
 use serde::{Deserialize, Serialize};
-use std::io;
+use std::{collections::BTreeMap, env, io};
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub(crate) enum Event {
     AccountCreated(AccountCreated),
     TransactionRecorded(TransactionRecorded),
     UnitCreated(UnitCreated),
     MoveAdded(MoveAdded),
+    FundsAllocated(FundsAllocated),
+    FundsReallocated(FundsReallocated),
+    RolloverPolicySet(RolloverPolicySet),
+    GoalSet(GoalSet),
+    OverspendingPolicySet(OverspendingPolicySet),
+    TransactionTagged(TransactionTagged),
+    TransactionUntagged(TransactionUntagged),
+    TransactionVoided(TransactionVoided),
+    MoveEdited(MoveEdited),
+    MoveRemoved(MoveRemoved),
+    BalanceAsserted(BalanceAsserted),
+    MoveCleared(MoveCleared),
+    Reconciled(Reconciled),
+    TransactionDateSet(TransactionDateSet),
+    AccountRenamed(AccountRenamed),
+    AccountClosed(AccountClosed),
+    AccountMetadataSet(AccountMetadataSet),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub(crate) struct AccountCreated {
     pub(crate) name: account::Name,
     pub(crate) kind: account::Kind,
+    #[serde(default)]
+    pub(crate) metadata: account::Metadata,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub(crate) struct TransactionRecorded {
     pub(crate) date: NaiveDate,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) payee: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -34,55 +71,786 @@ pub(crate) struct UnitCreated {
     pub(crate) decimal_places: u8,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub(crate) struct MoveAdded {
     pub(crate) transaction: transaction::Id,
     pub(crate) debit_account: account::Name,
     pub(crate) credit_account: account::Name,
     pub(crate) amount: NonNegativeAmount,
     pub(crate) unit: unit::Name,
+    #[serde(default)]
+    pub(crate) memo: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FundsAllocated {
+    pub(crate) envelope: account::Name,
+    pub(crate) amount: NonNegativeAmount,
+    pub(crate) unit: unit::Name,
+    pub(crate) month: Month,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FundsReallocated {
+    pub(crate) from_envelope: account::Name,
+    pub(crate) to_envelope: account::Name,
+    pub(crate) amount: NonNegativeAmount,
+    pub(crate) unit: unit::Name,
+    pub(crate) month: Month,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct RolloverPolicySet {
+    pub(crate) envelope: account::Name,
+    pub(crate) policy: rollover::Policy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct GoalSet {
+    pub(crate) envelope: account::Name,
+    pub(crate) goal: Goal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct OverspendingPolicySet {
+    pub(crate) policy: overspending::Policy,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TransactionTagged {
+    pub(crate) transaction: transaction::Id,
+    pub(crate) tag: tag::Name,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TransactionUntagged {
+    pub(crate) transaction: transaction::Id,
+    pub(crate) tag: tag::Name,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TransactionVoided {
+    pub(crate) transaction: transaction::Id,
+}
+
+/// Replaces every field of a previously added move but its transaction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MoveEdited {
+    pub(crate) move_: move_::Id,
+    pub(crate) debit_account: account::Name,
+    pub(crate) credit_account: account::Name,
+    pub(crate) amount: NonNegativeAmount,
+    pub(crate) unit: unit::Name,
+    pub(crate) memo: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MoveRemoved {
+    pub(crate) move_: move_::Id,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TransactionDateSet {
+    pub(crate) transaction: transaction::Id,
+    pub(crate) date: NaiveDate,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct BalanceAsserted {
+    pub(crate) account: account::Name,
+    pub(crate) date: NaiveDate,
+    pub(crate) amount: Amount,
+    pub(crate) unit: unit::Name,
+}
+
+/// Marks a move seen on a statement of one of its accounts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MoveCleared {
+    pub(crate) move_: move_::Id,
+    pub(crate) account: account::Name,
+    pub(crate) statement_date: NaiveDate,
+}
+
+/// Marks the cleared moves of an account reconciled against a statement
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Reconciled {
+    pub(crate) account: account::Name,
+    pub(crate) statement_date: NaiveDate,
+    pub(crate) statement_balance: Amount,
+    pub(crate) unit: unit::Name,
+}
+
+/// Renames the account and its descendants
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AccountRenamed {
+    pub(crate) from: account::Name,
+    pub(crate) to: account::Name,
+}
+
+/// No moves are allowed in the account after the date
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AccountClosed {
+    pub(crate) name: account::Name,
+    pub(crate) date: NaiveDate,
+}
+
+/// Replaces the metadata of the account
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AccountMetadataSet {
+    pub(crate) name: account::Name,
+    pub(crate) metadata: account::Metadata,
+}
+
+/// An event, along with when, by whom and how it was recorded
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Record {
+    #[serde(default)]
+    pub(crate) origin: Origin,
+    pub(crate) event: Event,
+}
+
+/// Unknown for events recorded before origins were kept
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub(crate) struct Origin {
+    #[serde(default)]
+    pub(crate) recorded_at: Option<DateTime<Local>>,
+    /// The operating system user
+    #[serde(default)]
+    pub(crate) user: Option<String>,
+    /// The arguments the program was run with
+    #[serde(default)]
+    pub(crate) arguments: Option<Vec<String>>,
+}
+
+impl Origin {
+    /// The origin of events recorded by this run of the program
+    pub(crate) fn current() -> Self {
+        Self {
+            recorded_at: Some(Local::now()),
+            user: env::var("USER").or_else(|_| env::var("USERNAME")).ok(),
+            arguments: Some(
+                env::args_os()
+                    .skip(1)
+                    .map(|argument| argument.to_string_lossy().into_owned())
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<Event> for Record {
+    fn from(event: Event) -> Self {
+        Self {
+            origin: Default::default(),
+            event,
+        }
+    }
 }
 
-#[derive(Debug)]
-pub(crate) struct Events(pub(super) Vec<Event>);
+#[derive(Debug, Default)]
+pub(crate) struct Events {
+    /// As persisted
+    recorded: Vec<Record>,
+    /// With every account name replaced by the account's current name
+    resolved: Vec<Event>,
+}
 
 impl Events {
-    pub(crate) fn iter(&self) -> std::slice::Iter<Event> {
-        self.0.iter()
+    pub(crate) fn iter(&self) -> std::slice::Iter<'_, Event> {
+        self.resolved.iter()
+    }
+    pub(crate) fn recorded(&self) -> &[Record] {
+        &self.recorded
     }
     pub(crate) fn try_from_reader(reader: &mut impl io::Read) -> Result<Events> {
         let contents = reader
             .read_into_string()
             .map_err(Error::EventsFailedToReadIntoString)?;
 
-        let events: Vec<Event> =
-            ron::from_str(&contents).map_err(Error::EventsFailedToDeserialize)?;
+        let records: Vec<Record> = match ron::from_str(&contents) {
+            Ok(records) => records,
+            // persisted before events were recorded along with their origin
+            Err(error) => ron::from_str::<Vec<Event>>(&contents)
+                .map_err(|_| Error::EventsFailedToDeserialize(error))?
+                .into_iter()
+                .map(Record::from)
+                .collect(),
+        };
         let validated_events =
-            events
+            records
                 .into_iter()
-                .try_fold(Events(vec![]), |mut validated_events, event| {
-                    validated_events.try_push(event)?;
+                .try_fold(Events::default(), |mut validated_events, record| {
+                    validated_events.try_push(record)?;
                     Ok::<Events, Error>(validated_events)
                 })?;
         Ok(validated_events)
     }
-    pub(crate) fn try_push(&mut self, event: Event) -> Result<()> {
-        event.validate_for_appending_to(self)?;
-        self.0.push(event);
+    /// The events as they were before any later ones were appended.
+    pub(crate) fn prefix(&self, len: usize) -> Events {
+        self.recorded[..len]
+            .iter()
+            .cloned()
+            .fold(Events::default(), |mut events, record| {
+                events.append(record);
+                events
+            })
+    }
+    /// The first `at_event` events, as they were recorded
+    pub(crate) fn at_event(&self, at_event: usize) -> Result<Events> {
+        if at_event > self.len() {
+            return Err(Error::EventsAtEventOutOfRange {
+                at_event,
+                len: self.len(),
+            });
+        }
+        Ok(self.prefix(at_event))
+    }
+    /// The events recorded at or before the local time;
+    /// events recorded at an unknown time are older than any others
+    pub(crate) fn at_time(&self, at_time: NaiveDateTime) -> Events {
+        let len = self
+            .recorded
+            .iter()
+            .take_while(|record| {
+                record
+                    .origin
+                    .recorded_at
+                    .is_none_or(|recorded_at| recorded_at.naive_local() <= at_time)
+            })
+            .count();
+        self.prefix(len)
+    }
+    pub(crate) fn len(&self) -> usize {
+        self.recorded.len()
+    }
+    /// Appends the event, unless it is invalid or it breaks a balance assertion;
+    /// only the assertions whose balances the event can change are checked again
+    pub(crate) fn try_push(&mut self, record: Record) -> Result<()> {
+        record.event.validate_for_appending_to(self)?;
+        let asserted = matches!(record.event, Event::BalanceAsserted(_))
+            || self
+                .iter()
+                .any(|event| matches!(event, Event::BalanceAsserted(_)));
+        let changes = if asserted {
+            self.balance_changes(&record.event)
+        } else {
+            Vec::new()
+        };
+        self.append(record);
+        let failure = self
+            .all_balance_assertions()
+            .into_iter()
+            .filter(|assertion| {
+                changes.iter().any(|(account, date)| {
+                    account.is_within(&assertion.account) && date <= &assertion.date
+                })
+            })
+            .find_map(|assertion| self.balance_assertion_failure(assertion));
+        if let Some(failure) = failure {
+            *self = self.prefix(self.len() - 1);
+            return Err(
+                EventValidateForAppendingToError::BalanceAssertionFailed(Box::new(failure)).into(),
+            );
+        }
         Ok(())
     }
+    fn append(&mut self, record: Record) {
+        if let Event::AccountRenamed(AccountRenamed { from, to }) = &record.event {
+            for resolved in &mut self.resolved {
+                resolved.rename_account(from, to);
+            }
+        }
+        self.resolved.push(record.event.clone());
+        self.recorded.push(record);
+    }
 }
 #[test]
 fn ron() {
-    let value = vec![Event::AccountCreated(AccountCreated {
-        name: account::Name("hello".into()),
-        kind: account::Kind::Budget,
-    })];
+    let value = vec![Record {
+        origin: Origin::current(),
+        event: Event::AccountCreated(AccountCreated {
+            name: account::Name("hello".into()),
+            kind: account::Kind::Budget,
+            metadata: Default::default(),
+        }),
+    }];
     let string = ron::to_string(&value).unwrap();
-    let parsed: Vec<Event> = ron::from_str(&string).unwrap();
+    let parsed: Vec<Record> = ron::from_str(&string).unwrap();
     assert_eq!(value, parsed);
 }
+#[test]
+fn ron_without_optional_fields() {
+    let string = r#"[
+        AccountCreated((name:("a"),kind:Budget)),
+        TransactionRecorded((date:"2022-08-27")),
+        MoveAdded((transaction:(1),debit_account:("a"),credit_account:("b"),amount:("1.00"),unit:("USD"))),
+    ]"#;
+    let parsed: Vec<Event> = ron::from_str(string).unwrap();
+    assert_eq!(
+        parsed,
+        vec![
+            Event::AccountCreated(AccountCreated {
+                name: account::Name("a".into()),
+                kind: account::Kind::Budget,
+                metadata: Default::default(),
+            }),
+            Event::TransactionRecorded(TransactionRecorded {
+                date: NaiveDate::from_ymd(2022, 8, 27),
+                description: None,
+                payee: None,
+            }),
+            Event::MoveAdded(MoveAdded {
+                transaction: transaction::Id(1),
+                debit_account: account::Name("a".into()),
+                credit_account: account::Name("b".into()),
+                amount: "1.00".parse().unwrap(),
+                unit: unit::Name("USD".into()),
+                memo: None,
+            }),
+        ]
+    );
+}
+#[test]
+fn moves_before_opening() {
+    let string = r#"[
+        AccountCreated((name:("a"),kind:Budget,metadata:(opened:Some("2022-08-27")))),
+        AccountCreated((name:("b"),kind:External)),
+        AccountCreated((name:("c"),kind:External,metadata:(opened:Some("2022-08-28")))),
+        UnitCreated((name:("USD"),decimal_places:2)),
+        TransactionRecorded((date:"2022-08-26")),
+        TransactionRecorded((date:"2022-08-27")),
+        MoveAdded((transaction:(2),debit_account:("b"),credit_account:("a"),amount:("1.00"),unit:("USD"))),
+    ]"#;
+    let mut events = Events::try_from_reader(&mut string.as_bytes()).unwrap();
+    let before_opening = Event::MoveAdded(MoveAdded {
+        transaction: transaction::Id(1),
+        debit_account: account::Name("b".into()),
+        credit_account: account::Name("a".into()),
+        amount: "1.00".parse().unwrap(),
+        unit: unit::Name("USD".into()),
+        memo: None,
+    });
+    assert!(events.try_push(before_opening.into()).is_err());
+    let edited_into_unopened = Event::MoveEdited(MoveEdited {
+        move_: move_::Id(1),
+        debit_account: account::Name("a".into()),
+        credit_account: account::Name("c".into()),
+        amount: "1.00".parse().unwrap(),
+        unit: unit::Name("USD".into()),
+        memo: None,
+    });
+    assert!(matches!(
+        events.try_push(edited_into_unopened.into()),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::MoveEdited(error)
+        )) if error.account_date.is_some()
+    ));
+    let dated_before_opening = Event::TransactionDateSet(TransactionDateSet {
+        transaction: transaction::Id(2),
+        date: NaiveDate::from_ymd_opt(2022, 8, 26).unwrap(),
+    });
+    assert!(matches!(
+        events.try_push(dated_before_opening.into()),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::TransactionDateSetAccountDate(move_::Id(1), _)
+        ))
+    ));
+    let opened_after_move = Event::AccountMetadataSet(AccountMetadataSet {
+        name: account::Name("b".into()),
+        metadata: account::Metadata {
+            opened: NaiveDate::from_ymd_opt(2022, 8, 28),
+            ..Default::default()
+        },
+    });
+    assert!(events.try_push(opened_after_move.into()).is_err());
+}
+#[test]
+fn assertions_checked_again_when_their_balances_change() {
+    let string = r#"[
+        AccountCreated((name:("a"),kind:Budget)),
+        AccountCreated((name:("a:b"),kind:Budget)),
+        AccountCreated((name:("c"),kind:External)),
+        UnitCreated((name:("USD"),decimal_places:2)),
+        TransactionRecorded((date:"2022-09-01")),
+        MoveAdded((transaction:(1),debit_account:("c"),credit_account:("a:b"),amount:("10.00"),unit:("USD"))),
+        BalanceAsserted((account:("a:b"),date:"2022-09-02",amount:("10.00"),unit:("USD"))),
+        TransactionRecorded((date:"2022-09-03")),
+    ]"#;
+    let mut events = Events::try_from_reader(&mut string.as_bytes()).unwrap();
+    let balance_assertion_failed = |result| {
+        matches!(
+            result,
+            Err(Error::EventValidateForAppendingTo(
+                EventValidateForAppendingToError::BalanceAssertionFailed(_)
+            ))
+        )
+    };
+    let move_added = |transaction| {
+        Record::from(Event::MoveAdded(MoveAdded {
+            transaction: transaction::Id(transaction),
+            debit_account: account::Name("c".into()),
+            credit_account: account::Name("a:b".into()),
+            amount: "1.00".parse().unwrap(),
+            unit: unit::Name("USD".into()),
+            memo: None,
+        }))
+    };
+    assert!(events.try_push(move_added(2)).is_ok());
+    let moved_before_assertion = Event::TransactionDateSet(TransactionDateSet {
+        transaction: transaction::Id(2),
+        date: NaiveDate::from_ymd_opt(2022, 9, 1).unwrap(),
+    });
+    assert!(balance_assertion_failed(
+        events.try_push(moved_before_assertion.into())
+    ));
+    assert!(balance_assertion_failed(events.try_push(move_added(1))));
+    assert!(balance_assertion_failed(
+        events.try_push(
+            Event::MoveRemoved(MoveRemoved {
+                move_: move_::Id(1)
+            })
+            .into()
+        )
+    ));
+    assert_eq!(events.len(), 9);
+}
+#[test]
+fn edits_checked_like_additions() {
+    let string = r#"[
+        AccountCreated((name:("a"),kind:Budget)),
+        AccountCreated((name:("b"),kind:External)),
+        UnitCreated((name:("USD"),decimal_places:2)),
+        OverspendingPolicySet((policy:Reject)),
+        TransactionRecorded((date:"2022-09-01")),
+        MoveAdded((transaction:(1),debit_account:("b"),credit_account:("a"),amount:("100.00"),unit:("USD"))),
+        TransactionRecorded((date:"2022-09-02")),
+        MoveAdded((transaction:(2),debit_account:("a"),credit_account:("b"),amount:("5.00"),unit:("USD"))),
+        TransactionRecorded((date:"2022-09-03")),
+        MoveAdded((transaction:(3),debit_account:("a"),credit_account:("b"),amount:("1.00"),unit:("USD"))),
+        TransactionVoided((transaction:(3))),
+    ]"#;
+    let mut events = Events::try_from_reader(&mut string.as_bytes()).unwrap();
+    let edit = |move_, amount: &str| {
+        Record::from(Event::MoveEdited(MoveEdited {
+            move_: move_::Id(move_),
+            debit_account: account::Name("a".into()),
+            credit_account: account::Name("b".into()),
+            amount: amount.parse().unwrap(),
+            unit: unit::Name("USD".into()),
+            memo: None,
+        }))
+    };
+    assert!(matches!(
+        events.try_push(edit(2, "900.00")),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::MoveEdited(error)
+        )) if error.overspending.is_some()
+    ));
+    assert!(matches!(
+        events.try_push(edit(3, "1.00")),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::MoveEdited(error)
+        )) if error.transaction_voided.is_some()
+    ));
+    // the original amount no longer counts
+    events.try_push(edit(2, "98.00")).unwrap();
+}
+#[test]
+fn removals_checked_for_overspending() {
+    let string = r#"[
+        AccountCreated((name:("a"),kind:Budget)),
+        AccountCreated((name:("b"),kind:External)),
+        UnitCreated((name:("USD"),decimal_places:2)),
+        OverspendingPolicySet((policy:Reject)),
+        TransactionRecorded((date:"2022-09-01")),
+        MoveAdded((transaction:(1),debit_account:("b"),credit_account:("a"),amount:("100.00"),unit:("USD"))),
+        TransactionRecorded((date:"2022-09-02")),
+        MoveAdded((transaction:(2),debit_account:("a"),credit_account:("b"),amount:("5.00"),unit:("USD"))),
+        TransactionRecorded((date:"2022-09-03")),
+        MoveAdded((transaction:(3),debit_account:("b"),credit_account:("a"),amount:("1.00"),unit:("USD"))),
+        TransactionVoided((transaction:(3))),
+    ]"#;
+    let mut events = Events::try_from_reader(&mut string.as_bytes()).unwrap();
+    let removed = |move_| {
+        Record::from(Event::MoveRemoved(MoveRemoved {
+            move_: move_::Id(move_),
+        }))
+    };
+    assert!(matches!(
+        events.try_push(removed(1)),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::MoveRemovedOverspending(move_::Id(1), _)
+        ))
+    ));
+    // a voided move no longer counts
+    events.try_push(removed(3)).unwrap();
+    events.try_push(removed(2)).unwrap();
+    events.try_push(removed(1)).unwrap();
+}
+#[test]
+fn moves_after_closing() {
+    let string = r#"[
+        AccountCreated((name:("a"),kind:Budget)),
+        AccountCreated((name:("b"),kind:External)),
+        AccountCreated((name:("c"),kind:External)),
+        UnitCreated((name:("USD"),decimal_places:2)),
+        TransactionRecorded((date:"2022-09-01")),
+        MoveAdded((transaction:(1),debit_account:("b"),credit_account:("a"),amount:("1.00"),unit:("USD"))),
+        TransactionRecorded((date:"2022-08-30")),
+        MoveAdded((transaction:(2),debit_account:("b"),credit_account:("c"),amount:("1.00"),unit:("USD"))),
+        MoveAdded((transaction:(2),debit_account:("c"),credit_account:("a"),amount:("1.00"),unit:("USD"))),
+        AccountClosed((name:("c"),date:"2022-08-31")),
+    ]"#;
+    let mut events = Events::try_from_reader(&mut string.as_bytes()).unwrap();
+    let edited_into_closed = Event::MoveEdited(MoveEdited {
+        move_: move_::Id(1),
+        debit_account: account::Name("b".into()),
+        credit_account: account::Name("c".into()),
+        amount: "1.00".parse().unwrap(),
+        unit: unit::Name("USD".into()),
+        memo: None,
+    });
+    assert!(matches!(
+        events.try_push(edited_into_closed.into()),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::MoveEdited(error)
+        )) if error.account_date.is_some()
+    ));
+    let dated_after_closing = Event::TransactionDateSet(TransactionDateSet {
+        transaction: transaction::Id(2),
+        date: NaiveDate::from_ymd_opt(2022, 9, 2).unwrap(),
+    });
+    assert!(matches!(
+        events.try_push(dated_after_closing.into()),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::TransactionDateSetAccountDate(move_::Id(2), _)
+        ))
+    ));
+    assert!(matches!(
+        events.try_push(
+            Event::MoveRemoved(MoveRemoved {
+                move_: move_::Id(3)
+            })
+            .into()
+        ),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::MoveRemovedAccountClosed(move_::Id(3), _)
+        ))
+    ));
+    let voided = Event::TransactionVoided(TransactionVoided {
+        transaction: transaction::Id(2),
+    });
+    assert!(matches!(
+        events.try_push(voided.into()),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::TransactionVoidedAccountClosed(move_::Id(2), _)
+        ))
+    ));
+    let amount_edited = Event::MoveEdited(MoveEdited {
+        move_: move_::Id(3),
+        debit_account: account::Name("c".into()),
+        credit_account: account::Name("a".into()),
+        amount: "2.00".parse().unwrap(),
+        unit: unit::Name("USD".into()),
+        memo: None,
+    });
+    assert!(events.try_push(amount_edited.into()).is_err());
+    let memo_edited = Event::MoveEdited(MoveEdited {
+        move_: move_::Id(3),
+        debit_account: account::Name("c".into()),
+        credit_account: account::Name("a".into()),
+        amount: "1.00".parse().unwrap(),
+        unit: unit::Name("USD".into()),
+        memo: Some("moved out".into()),
+    });
+    events.try_push(memo_edited.into()).unwrap();
+}
+#[test]
+fn clearing_per_account_and_statement() {
+    let string = r#"[
+        AccountCreated((name:("a"),kind:Budget)),
+        AccountCreated((name:("b"),kind:External)),
+        AccountCreated((name:("c"),kind:External)),
+        UnitCreated((name:("USD"),decimal_places:2)),
+        TransactionRecorded((date:"2022-09-01")),
+        TransactionRecorded((date:"2022-10-15")),
+        MoveAdded((transaction:(1),debit_account:("b"),credit_account:("a"),amount:("1.00"),unit:("USD"))),
+        MoveAdded((transaction:(2),debit_account:("b"),credit_account:("a"),amount:("2.00"),unit:("USD"))),
+    ]"#;
+    let mut events = Events::try_from_reader(&mut string.as_bytes()).unwrap();
+    let a = account::Name("a".into());
+    let b = account::Name("b".into());
+    let usd = unit::Name("USD".into());
+    let statement_date = NaiveDate::from_ymd(2022, 9, 30);
+    let clear = |move_: u64, account: &account::Name| {
+        Event::MoveCleared(MoveCleared {
+            move_: move_::Id(move_),
+            account: account.clone(),
+            statement_date,
+        })
+    };
+    assert!(matches!(
+        events.try_push(clear(1, &account::Name("c".into())).into()),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::MoveClearedAccountNotMoved(move_::Id(1), _)
+        ))
+    ));
+    assert!(matches!(
+        events.try_push(clear(2, &a).into()),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::MoveClearedAfterStatementDate(move_::Id(2), _)
+        ))
+    ));
+    events.try_push(clear(1, &a).into()).unwrap();
+    assert!(matches!(
+        events.try_push(clear(1, &a).into()),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::MoveClearedAlreadyCleared(move_::Id(1))
+        ))
+    ));
+    assert_eq!(
+        events.cleared_balance(&a, &usd, statement_date),
+        "1.00".parse().unwrap()
+    );
+    assert_eq!(
+        events.cleared_balance(&b, &usd, statement_date),
+        Amount::default()
+    );
+    events
+        .try_push(
+            Event::Reconciled(Reconciled {
+                account: a.clone(),
+                statement_date,
+                statement_balance: "1.00".parse().unwrap(),
+                unit: usd.clone(),
+            })
+            .into(),
+        )
+        .unwrap();
+    let move_ = events.get_move(&move_::Id(1)).unwrap();
+    assert_eq!(move_.status(&a), move_::Status::Reconciled);
+    assert_eq!(move_.status(&b), move_::Status::Pending);
+    events.try_push(clear(1, &b).into()).unwrap();
+    assert_eq!(
+        events.get_move(&move_::Id(1)).unwrap().status(&b),
+        move_::Status::Cleared
+    );
+    let edited = Event::MoveEdited(MoveEdited {
+        move_: move_::Id(1),
+        debit_account: b.clone(),
+        credit_account: account::Name("c".into()),
+        amount: "1.00".parse().unwrap(),
+        unit: usd.clone(),
+        memo: None,
+    });
+    assert!(matches!(
+        events.try_push(edited.into()),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::MoveEdited(error)
+        )) if error.move_cleared == Some(move_::Id(1))
+    ));
+    assert!(matches!(
+        events.try_push(
+            Event::MoveRemoved(MoveRemoved {
+                move_: move_::Id(1)
+            })
+            .into()
+        ),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::MoveRemovedMoveCleared(move_::Id(1))
+        ))
+    ));
+    let voided = Event::TransactionVoided(TransactionVoided {
+        transaction: transaction::Id(1),
+    });
+    assert!(matches!(
+        events.try_push(voided.into()),
+        Err(Error::EventValidateForAppendingTo(
+            EventValidateForAppendingToError::TransactionVoidedMoveCleared(move_::Id(1))
+        ))
+    ));
+}
+/// Lists the variant names once, for both [`Event::TYPE_NAMES`] and [`Event::type_name`];
+/// the match is exhaustive, so a variant missing here fails to compile
+macro_rules! type_names {
+    ($($variant:ident),* $(,)?) => {
+        impl Event {
+            /// The names of all variants, as given by [`Event::type_name`]
+            pub(crate) const TYPE_NAMES: [&'static str; [$(stringify!($variant)),*].len()] =
+                [$(stringify!($variant)),*];
+            /// The name of the variant, as persisted
+            pub(crate) fn type_name(&self) -> &'static str {
+                match self {
+                    $(Event::$variant(_) => stringify!($variant)),*
+                }
+            }
+        }
+    };
+}
+type_names!(
+    AccountCreated,
+    TransactionRecorded,
+    UnitCreated,
+    MoveAdded,
+    FundsAllocated,
+    FundsReallocated,
+    RolloverPolicySet,
+    GoalSet,
+    OverspendingPolicySet,
+    TransactionTagged,
+    TransactionUntagged,
+    TransactionVoided,
+    MoveEdited,
+    MoveRemoved,
+    BalanceAsserted,
+    MoveCleared,
+    Reconciled,
+    TransactionDateSet,
+    AccountRenamed,
+    AccountClosed,
+    AccountMetadataSet,
+);
 impl Event {
+    fn rename_account(&mut self, from: &account::Name, to: &account::Name) {
+        let names = match self {
+            Event::AccountCreated(AccountCreated { name, .. })
+            | Event::AccountClosed(AccountClosed { name, .. })
+            | Event::AccountMetadataSet(AccountMetadataSet { name, .. }) => vec![name],
+            Event::MoveAdded(MoveAdded {
+                debit_account,
+                credit_account,
+                ..
+            })
+            | Event::MoveEdited(MoveEdited {
+                debit_account,
+                credit_account,
+                ..
+            }) => vec![debit_account, credit_account],
+            Event::FundsAllocated(FundsAllocated { envelope, .. })
+            | Event::RolloverPolicySet(RolloverPolicySet { envelope, .. })
+            | Event::GoalSet(GoalSet { envelope, .. }) => vec![envelope],
+            Event::FundsReallocated(FundsReallocated {
+                from_envelope,
+                to_envelope,
+                ..
+            }) => vec![from_envelope, to_envelope],
+            Event::BalanceAsserted(BalanceAsserted { account, .. })
+            | Event::MoveCleared(MoveCleared { account, .. })
+            | Event::Reconciled(Reconciled { account, .. }) => vec![account],
+            Event::TransactionRecorded(_)
+            | Event::UnitCreated(_)
+            | Event::OverspendingPolicySet(_)
+            | Event::TransactionTagged(_)
+            | Event::TransactionUntagged(_)
+            | Event::TransactionVoided(_)
+            | Event::MoveRemoved(_)
+            | Event::TransactionDateSet(_)
+            | Event::AccountRenamed(_) => vec![],
+        };
+        for name in names {
+            name.rename(from, to);
+        }
+    }
     fn validate_for_appending_to(
         &self,
         events: &Events,
@@ -113,51 +881,559 @@ impl Event {
                 credit_account,
                 amount,
                 unit,
+                ..
             }) => {
                 let mut error: Option<EventValidateForAppendingToErrorMoveAdded> = None;
 
-                let transaction_found = events
-                    .all_transaction_ids()
-                    .into_iter()
-                    .contains(transaction);
-                if !transaction_found {
+                let found_transaction = events.get_transaction(transaction);
+                match &found_transaction {
+                    None => {
+                        error
+                            .get_or_insert(Default::default())
+                            .transaction_not_found = Some(*transaction);
+                    }
+                    Some(found) if found.voided => {
+                        error.get_or_insert(Default::default()).transaction_voided =
+                            Some(*transaction);
+                    }
+                    Some(_) => {}
+                }
+                let accounts = events.all_accounts();
+                if !accounts.contains_key(debit_account) {
                     error
                         .get_or_insert(Default::default())
-                        .transaction_not_found = Some(*transaction);
+                        .debit_account_not_found = Some(debit_account.clone());
                 }
-                let debit_account_found = events
-                    .all_account_names()
-                    .into_iter()
-                    .contains(debit_account);
-                if !debit_account_found {
+                if !accounts.contains_key(credit_account) {
+                    error
+                        .get_or_insert(Default::default())
+                        .credit_account_not_found = Some(credit_account.clone());
+                }
+                if let Some(unit_error) = validate_unit(events, unit, amount.scale()) {
+                    error.get_or_insert(Default::default()).unit = Some(unit_error);
+                }
+                if let Some(date) = found_transaction.map(|transaction| transaction.date) {
+                    if let Some(date_error) =
+                        validate_account_date(&accounts, [debit_account, credit_account], date)
+                    {
+                        error.get_or_insert(Default::default()).account_date = Some(date_error);
+                    }
+                }
+                if events.overspending_policy() == overspending::Policy::Reject {
+                    let overspending = events.overspending(&Move {
+                        id: events.next_move_id(),
+                        transaction: *transaction,
+                        debit_account: debit_account.clone(),
+                        credit_account: credit_account.clone(),
+                        amount: *amount,
+                        unit: unit.clone(),
+                        memo: None,
+                        debit_status: Default::default(),
+                        credit_status: Default::default(),
+                    });
+                    if let Some(overspending) = overspending {
+                        error.get_or_insert(Default::default()).overspending =
+                            Some(Box::new(overspending));
+                    }
+                }
+                if let Some(error) = error {
+                    Err(EventValidateForAppendingToError::MoveAdded(Box::new(error)))
+                } else {
+                    Ok(())
+                }
+            }
+            Event::MoveEdited(MoveEdited {
+                move_,
+                debit_account,
+                credit_account,
+                amount,
+                unit,
+                ..
+            }) => {
+                let mut error: Option<EventValidateForAppendingToErrorMoveEdited> = None;
+
+                let found_move = events.get_move(move_);
+                match &found_move {
+                    None => {
+                        error.get_or_insert(Default::default()).move_not_found = Some(*move_);
+                    }
+                    Some(found) if found.is_cleared() => {
+                        error.get_or_insert(Default::default()).move_cleared = Some(*move_);
+                    }
+                    Some(_) => {}
+                }
+                let accounts = events.all_accounts();
+                if !accounts.contains_key(debit_account) {
                     error
                         .get_or_insert(Default::default())
                         .debit_account_not_found = Some(debit_account.clone());
                 }
-                let credit_account_found = events
-                    .all_account_names()
-                    .into_iter()
-                    .contains(credit_account);
-                if !credit_account_found {
+                if !accounts.contains_key(credit_account) {
                     error
                         .get_or_insert(Default::default())
                         .credit_account_not_found = Some(credit_account.clone());
                 }
-                let Some(unit) = events.get_unit(unit) else {
-                    let mut error = error.unwrap_or_default();
-                    error.unit = Some(EventValidateForAppendingToErrorMoveAddedUnit::UnitNotFound(unit.clone()));
-                    return Err(EventValidateForAppendingToError::MoveAdded(error));
+                let found_transaction = found_move
+                    .as_ref()
+                    .and_then(|found| events.get_transaction(&found.transaction));
+                if let Some(found_transaction) = found_transaction {
+                    if found_transaction.voided {
+                        error.get_or_insert(Default::default()).transaction_voided =
+                            Some(found_transaction.id);
+                    }
+                    if let Some(date_error) = validate_account_date(
+                        &accounts,
+                        [debit_account, credit_account],
+                        found_transaction.date,
+                    ) {
+                        error.get_or_insert(Default::default()).account_date = Some(date_error);
+                    }
+                }
+                if let Some(found) = &found_move {
+                    // only the memo of a move of a closed account can change
+                    let changed = &found.debit_account != debit_account
+                        || &found.credit_account != credit_account
+                        || found.amount != *amount
+                        || &found.unit != unit;
+                    if let Some(closed) = closed_account_of(&accounts, found).filter(|_| changed) {
+                        error.get_or_insert(Default::default()).account_date = Some(
+                            EventValidateForAppendingToErrorAccountDate::AccountClosed(closed),
+                        );
+                    }
+                }
+                if let Some(found) = found_move {
+                    if events.overspending_policy() == overspending::Policy::Reject {
+                        // as if the move had never been added
+                        let mut without = events.prefix(events.len());
+                        without.append(Event::MoveRemoved(MoveRemoved { move_: *move_ }).into());
+                        let overspending = without.overspending(&Move {
+                            debit_account: debit_account.clone(),
+                            credit_account: credit_account.clone(),
+                            amount: *amount,
+                            unit: unit.clone(),
+                            ..found
+                        });
+                        if let Some(overspending) = overspending {
+                            error.get_or_insert(Default::default()).overspending =
+                                Some(Box::new(overspending));
+                        }
+                    }
+                }
+                if let Some(unit_error) = validate_unit(events, unit, amount.scale()) {
+                    error.get_or_insert(Default::default()).unit = Some(unit_error);
+                }
+                if let Some(error) = error {
+                    Err(EventValidateForAppendingToError::MoveEdited(Box::new(
+                        error,
+                    )))
+                } else {
+                    Ok(())
+                }
+            }
+            Event::MoveRemoved(MoveRemoved { move_ }) => match events.get_move(move_) {
+                None => Err(EventValidateForAppendingToError::MoveRemovedMoveNotFound(
+                    *move_,
+                )),
+                Some(found) if found.is_cleared() => Err(
+                    EventValidateForAppendingToError::MoveRemovedMoveCleared(*move_),
+                ),
+                Some(found) => {
+                    if let Some(closed) = closed_account_of(&events.all_accounts(), &found) {
+                        return Err(EventValidateForAppendingToError::MoveRemovedAccountClosed(
+                            *move_, closed,
+                        ));
+                    }
+                    let voided = events
+                        .get_transaction(&found.transaction)
+                        .is_some_and(|transaction| transaction.voided);
+                    if !voided && events.overspending_policy() == overspending::Policy::Reject {
+                        // removing a move moves its amount back
+                        let overspending = events.overspending(&Move {
+                            debit_account: found.credit_account.clone(),
+                            credit_account: found.debit_account.clone(),
+                            ..found
+                        });
+                        if let Some(overspending) = overspending {
+                            return Err(EventValidateForAppendingToError::MoveRemovedOverspending(
+                                *move_,
+                                Box::new(overspending),
+                            ));
+                        }
+                    }
+                    Ok(())
+                }
+            },
+            Event::FundsAllocated(FundsAllocated {
+                envelope,
+                amount,
+                unit,
+                ..
+            }) => {
+                let mut error: Option<EventValidateForAppendingToErrorFundsAllocated> = None;
+
+                if let Some(envelope_error) = validate_envelope(events, envelope) {
+                    error.get_or_insert(Default::default()).envelope = Some(envelope_error);
+                }
+                if let Some(unit_error) = validate_unit(events, unit, amount.scale()) {
+                    error.get_or_insert(Default::default()).unit = Some(unit_error);
+                }
+                let available = events
+                    .to_be_budgeted()
+                    .get(unit)
+                    .copied()
+                    .unwrap_or_default();
+                if Amount::from(*amount) > available {
+                    error.get_or_insert(Default::default()).insufficient_funds =
+                        Some(EventValidateForAppendingToErrorInsufficientFunds {
+                            available,
+                            requested: (*amount).into(),
+                        });
+                }
+                if let Some(error) = error {
+                    Err(EventValidateForAppendingToError::FundsAllocated(Box::new(
+                        error,
+                    )))
+                } else {
+                    Ok(())
+                }
+            }
+            Event::FundsReallocated(FundsReallocated {
+                from_envelope,
+                to_envelope,
+                amount,
+                unit,
+                month,
+            }) => {
+                let mut error: Option<EventValidateForAppendingToErrorFundsReallocated> = None;
+
+                if let Some(envelope_error) = validate_envelope(events, from_envelope) {
+                    error.get_or_insert(Default::default()).from_envelope = Some(envelope_error);
+                }
+                if let Some(envelope_error) = validate_envelope(events, to_envelope) {
+                    error.get_or_insert(Default::default()).to_envelope = Some(envelope_error);
+                }
+                if let Some(unit_error) = validate_unit(events, unit, amount.scale()) {
+                    error.get_or_insert(Default::default()).unit = Some(unit_error);
+                }
+                // funds allocated ahead are available in their month
+                let month = events
+                    .current_month()
+                    .map_or(*month, |current_month| current_month.max(*month));
+                let available = events
+                    .budget_month(month)
+                    .get(from_envelope)
+                    .and_then(|units| units.get(unit))
+                    .map(|envelope_month| envelope_month.available)
+                    .unwrap_or_default();
+                if Amount::from(*amount) > available {
+                    error.get_or_insert(Default::default()).insufficient_funds =
+                        Some(EventValidateForAppendingToErrorInsufficientFunds {
+                            available,
+                            requested: (*amount).into(),
+                        });
+                }
+                if let Some(error) = error {
+                    Err(EventValidateForAppendingToError::FundsReallocated(
+                        Box::new(error),
+                    ))
+                } else {
+                    Ok(())
+                }
+            }
+            Event::RolloverPolicySet(RolloverPolicySet { envelope, .. }) => {
+                match validate_envelope(events, envelope) {
+                    Some(error) => Err(EventValidateForAppendingToError::RolloverPolicySet(error)),
+                    None => Ok(()),
+                }
+            }
+            Event::OverspendingPolicySet(_) => Ok(()),
+            Event::TransactionTagged(TransactionTagged { transaction, tag }) => {
+                let Some(transaction) = events.get_transaction(transaction) else {
+                    return Err(
+                        EventValidateForAppendingToError::TransactionTaggedTransactionNotFound(
+                            *transaction,
+                        ),
+                    );
                 };
-                if amount.scale() != unit.decimal_places as u32 {
-                    error.get_or_insert(Default::default()).unit = Some(
-                        EventValidateForAppendingToErrorMoveAddedUnit::DecimalPlacesMismatch {
-                            unit_scale: unit.decimal_places,
-                            amount_scale: amount.scale(),
+                if transaction.tags.contains(tag) {
+                    Err(
+                        EventValidateForAppendingToError::TransactionTaggedAlreadyTagged(
+                            tag.clone(),
+                        ),
+                    )
+                } else {
+                    Ok(())
+                }
+            }
+            Event::TransactionUntagged(TransactionUntagged { transaction, tag }) => {
+                let tagged = events
+                    .get_transaction(transaction)
+                    .is_some_and(|transaction| transaction.tags.contains(tag));
+                if tagged {
+                    Ok(())
+                } else {
+                    Err(EventValidateForAppendingToError::TransactionUntaggedNotTagged(tag.clone()))
+                }
+            }
+            Event::TransactionVoided(TransactionVoided { transaction }) => {
+                match events.get_transaction(transaction) {
+                    None => Err(
+                        EventValidateForAppendingToError::TransactionVoidedTransactionNotFound(
+                            *transaction,
+                        ),
+                    ),
+                    Some(found) if found.voided => Err(
+                        EventValidateForAppendingToError::TransactionVoidedAlreadyVoided(
+                            *transaction,
+                        ),
+                    ),
+                    Some(_) => {
+                        let accounts = events.all_accounts();
+                        let moves = events.all_moves();
+                        for move_ in moves.filter(|move_| &move_.transaction == transaction) {
+                            if move_.is_cleared() {
+                                return Err(
+                                    EventValidateForAppendingToError::TransactionVoidedMoveCleared(
+                                        move_.id,
+                                    ),
+                                );
+                            }
+                            if let Some(closed) = closed_account_of(&accounts, &move_) {
+                                return Err(
+                                    EventValidateForAppendingToError::TransactionVoidedAccountClosed(
+                                        move_.id, closed,
+                                    ),
+                                );
+                            }
+                        }
+                        Ok(())
+                    }
+                }
+            }
+            Event::BalanceAsserted(BalanceAsserted {
+                account,
+                amount,
+                unit,
+                ..
+            }) => {
+                let mut error: Option<EventValidateForAppendingToErrorBalanceAsserted> = None;
+
+                if events.get_account(account).is_none() {
+                    error.get_or_insert(Default::default()).account_not_found =
+                        Some(account.clone());
+                }
+                if let Some(unit_error) = validate_unit(events, unit, amount.0.scale()) {
+                    error.get_or_insert(Default::default()).unit = Some(unit_error);
+                }
+                if let Some(error) = error {
+                    Err(EventValidateForAppendingToError::BalanceAsserted(error))
+                } else {
+                    Ok(())
+                }
+            }
+            Event::MoveCleared(MoveCleared {
+                move_,
+                account,
+                statement_date,
+            }) => {
+                let Some(found) = events.get_move(move_) else {
+                    return Err(EventValidateForAppendingToError::MoveClearedMoveNotFound(
+                        *move_,
+                    ));
+                };
+                if ![&found.debit_account, &found.credit_account].contains(&account) {
+                    return Err(
+                        EventValidateForAppendingToError::MoveClearedAccountNotMoved(
+                            *move_,
+                            account.clone(),
+                        ),
+                    );
+                }
+                let date = events
+                    .get_transaction(&found.transaction)
+                    .map(|transaction| transaction.date);
+                if date.is_some_and(|date| date > *statement_date) {
+                    return Err(
+                        EventValidateForAppendingToError::MoveClearedAfterStatementDate(
+                            *move_,
+                            *statement_date,
+                        ),
+                    );
+                }
+                if found.status(account) == move_::Status::Pending {
+                    Ok(())
+                } else {
+                    Err(EventValidateForAppendingToError::MoveClearedAlreadyCleared(
+                        *move_,
+                    ))
+                }
+            }
+            Event::Reconciled(Reconciled {
+                account,
+                statement_date,
+                statement_balance,
+                unit,
+            }) => {
+                let mut error: Option<EventValidateForAppendingToErrorReconciled> = None;
+
+                if events.get_account(account).is_none() {
+                    error.get_or_insert(Default::default()).account_not_found =
+                        Some(account.clone());
+                }
+                if let Some(unit_error) = validate_unit(events, unit, statement_balance.0.scale()) {
+                    error.get_or_insert(Default::default()).unit = Some(unit_error);
+                }
+                let cleared_balance = events.cleared_balance(account, unit, *statement_date);
+                if cleared_balance != *statement_balance {
+                    error
+                        .get_or_insert(Default::default())
+                        .cleared_balance_mismatch = Some(cleared_balance);
+                }
+                if let Some(error) = error {
+                    Err(EventValidateForAppendingToError::Reconciled(error))
+                } else {
+                    Ok(())
+                }
+            }
+            Event::AccountClosed(AccountClosed { name, date }) => {
+                let Some(account) = events.get_account(name) else {
+                    return Err(EventValidateForAppendingToError::AccountClosed(Box::new(
+                        EventValidateForAppendingToErrorAccountClosed {
+                            account_not_found: Some(name.clone()),
+                            ..Default::default()
                         },
+                    )));
+                };
+                let mut error: Option<EventValidateForAppendingToErrorAccountClosed> = None;
+
+                if let Some(closed) = account.closed {
+                    error.get_or_insert(Default::default()).already_closed = Some(closed);
+                }
+                let non_zero_balance = events
+                    .all_balances(&Default::default())
+                    .remove(name)
+                    .unwrap_or_default()
+                    .0
+                    .into_iter()
+                    .find(|(_unit, amount)| *amount != Amount::default());
+                if let Some(non_zero_balance) = non_zero_balance {
+                    error.get_or_insert(Default::default()).non_zero_balance =
+                        Some(non_zero_balance);
+                }
+                let transactions = events.all_transactions();
+                let later_move = events.all_moves().find(|move_| {
+                    [&move_.debit_account, &move_.credit_account].contains(&name)
+                        && transactions[&move_.transaction].date > *date
+                });
+                if let Some(later_move) = later_move {
+                    error.get_or_insert(Default::default()).later_move = Some(later_move.id);
+                }
+                if let Some(error) = error {
+                    Err(EventValidateForAppendingToError::AccountClosed(Box::new(
+                        error,
+                    )))
+                } else {
+                    Ok(())
+                }
+            }
+            Event::AccountMetadataSet(AccountMetadataSet { name, metadata }) => {
+                if events.get_account(name).is_none() {
+                    return Err(EventValidateForAppendingToError::AccountMetadataSet(
+                        EventValidateForAppendingToErrorAccountMetadataSet {
+                            account_not_found: Some(name.clone()),
+                            ..Default::default()
+                        },
+                    ));
+                }
+                let Some(opened) = metadata.opened else {
+                    return Ok(());
+                };
+                let transactions = events.all_transactions();
+                let earlier_move = events.all_moves().find(|move_| {
+                    [&move_.debit_account, &move_.credit_account].contains(&name)
+                        && transactions[&move_.transaction].date < opened
+                });
+                match earlier_move {
+                    Some(earlier_move) => {
+                        Err(EventValidateForAppendingToError::AccountMetadataSet(
+                            EventValidateForAppendingToErrorAccountMetadataSet {
+                                earlier_move: Some(earlier_move.id),
+                                ..Default::default()
+                            },
+                        ))
+                    }
+                    None => Ok(()),
+                }
+            }
+            Event::AccountRenamed(AccountRenamed { from, to }) => {
+                let account_names = events.all_account_names();
+                let renamed = account_names
+                    .iter()
+                    .filter(|name| name.is_within(from))
+                    .collect::<Vec<_>>();
+                if renamed.is_empty() {
+                    return Err(
+                        EventValidateForAppendingToError::AccountRenamedAccountNotFound(
+                            from.clone(),
+                        ),
+                    );
+                }
+                let collision = renamed.into_iter().find_map(|name| {
+                    let mut name = name.clone();
+                    name.rename(from, to);
+                    account_names
+                        .iter()
+                        .find(|existing| !existing.is_within(from) && **existing == name)
+                        .cloned()
+                });
+                match collision {
+                    Some(name) => {
+                        Err(EventValidateForAppendingToError::AccountRenamedNameCollision(name))
+                    }
+                    None => Ok(()),
+                }
+            }
+            Event::TransactionDateSet(TransactionDateSet { transaction, date }) => {
+                if events.get_transaction(transaction).is_none() {
+                    return Err(
+                        EventValidateForAppendingToError::TransactionDateSetTransactionNotFound(
+                            *transaction,
+                        ),
                     );
                 }
+                let accounts = events.all_accounts();
+                let date_error = events
+                    .all_moves()
+                    .filter(|move_| move_.transaction == *transaction)
+                    .find_map(|move_| {
+                        validate_account_date(
+                            &accounts,
+                            [&move_.debit_account, &move_.credit_account],
+                            *date,
+                        )
+                        .map(|date_error| (move_.id, date_error))
+                    });
+                match date_error {
+                    Some((move_, date_error)) => Err(
+                        EventValidateForAppendingToError::TransactionDateSetAccountDate(
+                            move_, date_error,
+                        ),
+                    ),
+                    None => Ok(()),
+                }
+            }
+            Event::GoalSet(GoalSet { envelope, goal }) => {
+                let mut error: Option<EventValidateForAppendingToErrorGoalSet> = None;
+
+                if let Some(envelope_error) = validate_envelope(events, envelope) {
+                    error.get_or_insert(Default::default()).envelope = Some(envelope_error);
+                }
+                if let Some(unit_error) = validate_unit(events, goal.unit(), goal.amount().scale())
+                {
+                    error.get_or_insert(Default::default()).unit = Some(unit_error);
+                }
                 if let Some(error) = error {
-                    Err(EventValidateForAppendingToError::MoveAdded(error))
+                    Err(EventValidateForAppendingToError::GoalSet(error))
                 } else {
                     Ok(())
                 }
@@ -165,3 +1441,78 @@ impl Event {
         }
     }
 }
+
+fn validate_unit(
+    events: &Events,
+    unit: &unit::Name,
+    amount_scale: u32,
+) -> Option<EventValidateForAppendingToErrorUnit> {
+    let Some(unit) = events.get_unit(unit) else {
+        return Some(EventValidateForAppendingToErrorUnit::UnitNotFound(
+            unit.clone(),
+        ));
+    };
+    if amount_scale != unit.decimal_places as u32 {
+        Some(
+            EventValidateForAppendingToErrorUnit::DecimalPlacesMismatch {
+                unit_scale: unit.decimal_places,
+                amount_scale,
+            },
+        )
+    } else {
+        None
+    }
+}
+
+/// Moves are only allowed in an account while it is open
+fn validate_account_date(
+    accounts: &BTreeMap<account::Name, Account>,
+    move_accounts: [&account::Name; 2],
+    date: NaiveDate,
+) -> Option<EventValidateForAppendingToErrorAccountDate> {
+    move_accounts.into_iter().find_map(|name| {
+        let account = accounts.get(name)?;
+        if account.closed.is_some_and(|closed| date > closed) {
+            Some(EventValidateForAppendingToErrorAccountDate::AccountClosed(
+                name.clone(),
+            ))
+        } else if account.metadata.opened.is_some_and(|opened| date < opened) {
+            Some(EventValidateForAppendingToErrorAccountDate::AccountNotOpened(name.clone()))
+        } else {
+            None
+        }
+    })
+}
+
+/// A closed account of the move, whose balance taking the move back would change
+fn closed_account_of(
+    accounts: &BTreeMap<account::Name, Account>,
+    move_: &Move,
+) -> Option<account::Name> {
+    [&move_.debit_account, &move_.credit_account]
+        .into_iter()
+        .find(|name| {
+            accounts
+                .get(*name)
+                .is_some_and(|account| account.closed.is_some())
+        })
+        .cloned()
+}
+
+fn validate_envelope(
+    events: &Events,
+    envelope: &account::Name,
+) -> Option<EventValidateForAppendingToErrorEnvelope> {
+    match events.get_account(envelope) {
+        None => Some(EventValidateForAppendingToErrorEnvelope::AccountNotFound(
+            envelope.clone(),
+        )),
+        Some(Account {
+            kind: account::Kind::Budget,
+            ..
+        }) => None,
+        Some(_) => Some(EventValidateForAppendingToErrorEnvelope::AccountNotBudget(
+            envelope.clone(),
+        )),
+    }
+}
@@ -4,10 +4,20 @@ mod arguments_interpreter;
 mod cli;
 mod entities {
     pub(crate) mod account;
+    pub(crate) mod activity;
+    pub(crate) mod allocation;
     pub(crate) mod amount;
+    pub(crate) mod assertion;
     pub(crate) mod balance;
+    pub(crate) mod envelope_month;
+    pub(crate) mod filter;
+    pub(crate) mod finding;
+    pub(crate) mod goal;
+    pub(crate) mod month;
     pub(crate) mod move_;
-    pub(crate) mod sum;
+    pub(crate) mod overspending;
+    pub(crate) mod rollover;
+    pub(crate) mod tag;
     pub(crate) mod transaction;
     pub(crate) mod unit;
 }
@@ -24,15 +34,12 @@ use std::{
     path::PathBuf,
 };
 
-use events::{Event, Events};
+use events::{Events, Origin, Record};
 
 fn main() {
     let args_os = std::env::args_os();
     // TODO see whether all the validation can be done here
     let arguments = cli::Arguments::try_parse_from(args_os).unwrap();
-    // TODO introduce struct for return type
-    let arguments_interpreter::Actions { event, report } =
-        arguments_interpreter::interpret(arguments).unwrap();
     // TODO default persistence file path
     let persistence_file_path = PathBuf::from(env::var("PERSISTENCE_FILE").unwrap());
     let mut file_options = fs::OpenOptions::new();
@@ -46,7 +53,7 @@ fn main() {
                 .map_err(Error::PersistenceFileOpenFailed)?;
             new_persistence_file
                 .write_all(
-                    ron::to_string(&Vec::<Event>::new())
+                    ron::to_string(&Vec::<Record>::new())
                         .map_err(Error::EventsFailedToSerialize)?
                         .as_bytes(),
                 )
@@ -58,11 +65,31 @@ fn main() {
         })
         .unwrap();
     let mut events = Events::try_from_reader(&mut persistence_file).unwrap();
-    if let Some(event) = event {
-        events.try_push(event).unwrap();
+    if let Some(at_event) = arguments.at_event {
+        events = events.at_event(at_event).unwrap();
+    }
+    if let Some(at_time) = arguments.at_time {
+        events = events.at_time(at_time);
+    }
+    // TODO introduce struct for return type
+    let arguments_interpreter::Actions {
+        events: new_events,
+        report,
+    } = arguments_interpreter::interpret(arguments, &events).unwrap();
+    // nothing is persisted unless every new event is valid
+    if !new_events.is_empty() {
+        let origin = Origin::current();
+        for event in new_events {
+            events
+                .try_push(Record {
+                    origin: origin.clone(),
+                    event,
+                })
+                .unwrap();
+        }
         persistence_file.rewind().unwrap();
         persistence_file.set_len(0).unwrap();
-        ron::ser::to_writer(&mut persistence_file, &events.0).unwrap();
+        ron::ser::to_writer(&mut persistence_file, events.recorded()).unwrap();
     }
     if let Some(report) = report {
         let report = report.compile(&events).unwrap();
@@ -1,24 +1,75 @@
-use std::{collections::BTreeMap, ops};
+use std::collections::BTreeMap;
 
 use crate::error::{Error, Result};
 use crate::{
-    entities::{account, amount::Amount, transaction, unit},
-    events::Events,
+    entities::{
+        account,
+        amount::Amount,
+        balance::Balance,
+        filter::Filter,
+        finding::Finding,
+        goal::Goal,
+        month::Month,
+        move_::{self, Move},
+        overspending::Overspending,
+        transaction::{self, Transaction},
+        unit,
+    },
+    events::{self, Event, Events, Origin, Record},
 };
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
 use cli_table::{Cell, Row, Table};
 use itertools::Itertools;
 
 pub(crate) enum Report {
     TransactionRecordResponse,
-    Balances,
+    MoveAddResponse,
+    Balances {
+        filter: Filter,
+        include_closed: bool,
+    },
+    BalancesByKind {
+        filter: Filter,
+        include_closed: bool,
+    },
     RunningBalance {
         account: account::Name,
         unit: unit::Name,
+        filter: Filter,
+    },
+    TransactionList {
+        filter: Filter,
     },
     TransactionShow {
         id: transaction::Id,
     },
+    MoveShow {
+        id: move_::Id,
+    },
+    AccountList,
+    AccountShow {
+        name: account::Name,
+        recent: usize,
+    },
+    ToBeBudgeted,
+    BudgetVsActual {
+        month: Month,
+    },
+    Goals,
+    Check {
+        strict: bool,
+    },
+    History {
+        types: Vec<String>,
+        since: Option<NaiveDateTime>,
+        until: Option<NaiveDateTime>,
+    },
+    Reconcile {
+        account: account::Name,
+        statement_date: NaiveDate,
+        statement_balance: Amount,
+        unit: unit::Name,
+    },
 }
 
 impl Report {
@@ -26,29 +77,116 @@ impl Report {
         let output = match self {
             Report::TransactionRecordResponse => {
                 let last_transaction_id = events.last_transaction_id();
-                format!("Recorded transaction {last_transaction_id}\n")
+                let added = events
+                    .all_moves()
+                    .filter(|move_| move_.transaction == last_transaction_id)
+                    .count();
+                format!(
+                    "Recorded transaction {last_transaction_id}\n{}",
+                    overspending_warnings(events, added)
+                )
+            }
+            Report::MoveAddResponse => overspending_warnings(events, 1),
+            Report::Balances {
+                filter,
+                include_closed,
+            } => {
+                let accounts = events.all_accounts();
+                format_table(
+                    events
+                        .rolled_up_balances(filter)
+                        .into_iter()
+                        .filter(|(name, _balance)| {
+                            *include_closed
+                                || accounts
+                                    .get(name)
+                                    .is_none_or(|account| !account.closed_as_of(filter.until))
+                        })
+                        .map(|(name, balance)| {
+                            let sums = balance.0.into_iter().flat_map(|(name, amount)| {
+                                [
+                                    amount
+                                        .0
+                                        .to_string()
+                                        .cell()
+                                        .justify(cli_table::format::Justify::Right),
+                                    name.0.cell(),
+                                ]
+                            });
+                            let indented_name =
+                                format!("{}{}", "  ".repeat(name.depth()), name.leaf());
+                            [indented_name.cell()].into_iter().chain(sums)
+                        }),
+                    ["account", "balance", ""],
+                )
             }
-            Report::Balances => format_table(
-                events.all_balances().into_iter().map(|(name, balance)| {
-                    let sums = balance.0.into_iter().flat_map(|(name, amount)| {
+            Report::BalancesByKind {
+                filter,
+                include_closed,
+            } => {
+                let accounts = events.all_accounts();
+                let balances = events
+                    .all_signed_balances(filter)
+                    .into_iter()
+                    .filter_map(|(name, balance)| {
+                        let account = accounts.get(&name)?;
+                        if account.closed_as_of(filter.until) && !include_closed {
+                            return None;
+                        }
+                        Some((account.kind, name, balance))
+                    })
+                    .sorted_by(|(kind, name, _), (other_kind, other_name, _)| {
+                        (kind, name).cmp(&(other_kind, other_name))
+                    });
+                let mut rows = Vec::<[String; 4]>::new();
+                for (kind, accounts) in &balances.group_by(|(kind, _name, _balance)| *kind) {
+                    let mut totals = BTreeMap::<unit::Name, Amount>::new();
+                    for (_kind, name, balance) in accounts {
+                        for (unit, amount) in balance.0 {
+                            *totals.entry(unit.clone()).or_default() += amount;
+                            rows.push([
+                                kind.to_string(),
+                                name.to_string(),
+                                amount.to_string(),
+                                unit.0,
+                            ]);
+                        }
+                    }
+                    rows.extend(totals.into_iter().map(|(unit, total)| {
+                        [kind.to_string(), "total".into(), total.to_string(), unit.0]
+                    }));
+                }
+                rows.extend(events.net_worth(filter).into_iter().map(|(unit, amount)| {
+                    [
+                        "net worth".into(),
+                        String::new(),
+                        amount.to_string(),
+                        unit.0,
+                    ]
+                }));
+                format_table(
+                    rows.into_iter().map(|[kind, account, amount, unit]| {
                         [
-                            amount
-                                .0
-                                .to_string()
-                                .cell()
-                                .justify(cli_table::format::Justify::Right),
-                            name.0.cell(),
+                            kind.cell(),
+                            account.cell(),
+                            amount.cell().justify(cli_table::format::Justify::Right),
+                            unit.cell(),
                         ]
-                    });
-                    [name.0.cell()].into_iter().chain(sums)
-                }),
-                ["account", "balance", ""],
-            ),
-            Report::RunningBalance { account, unit } => format_table(
+                    }),
+                    ["kind", "account", "balance", ""],
+                )
+            }
+            Report::RunningBalance {
+                account,
+                unit,
+                filter,
+            } => format_table(
                 events
-                    .all_moves()
+                    .filtered_moves(filter)
+                    .into_iter()
                     .filter_map(|move_| {
-                        if [&move_.debit_account, &move_.credit_account].contains(&account)
+                        if (move_.debit_account.is_within(account)
+                            || move_.credit_account.is_within(account))
                             && move_.unit == *unit
                         {
                             Some((events.get_transaction(&move_.transaction)?, move_))
@@ -56,63 +194,425 @@ impl Report {
                             None
                         }
                     })
-                    .sorted_by_key(|(transaction, _move)| transaction.id)
+                    .sorted_by_key(|(transaction, _move)| (transaction.date, transaction.id))
                     .fold(
                         (
-                            BTreeMap::<transaction::Id, (NaiveDate, Amount, Amount)>::new(),
+                            BTreeMap::<
+                                (NaiveDate, transaction::Id),
+                                (Transaction, Amount, Amount, Vec<String>, move_::Status),
+                            >::new(),
                             Amount::default(),
                         ),
                         |(mut rows, running_balance), (transaction, move_)| {
-                            let (_transaction_date, row_affect, row_balance) = rows
-                                .entry(transaction.id)
-                                .or_insert((transaction.date, Default::default(), running_balance));
-                            let operation = if account == &move_.debit_account {
-                                ops::SubAssign::sub_assign
-                            } else if account == &move_.credit_account {
-                                ops::AddAssign::add_assign
-                            } else {
-                                unreachable!()
-                            };
-                            operation(row_affect, move_.amount.into());
-                            operation(row_balance, move_.amount.into());
+                            // a move between two descendants affects neither
+                            let mut affect = Amount::default();
+                            let mut status = move_::Status::Reconciled;
+                            if move_.debit_account.is_within(account) {
+                                affect -= move_.amount.into();
+                                status = status.min(move_.debit_status);
+                            }
+                            if move_.credit_account.is_within(account) {
+                                affect += move_.amount.into();
+                                status = status.min(move_.credit_status);
+                            }
+                            let (_transaction, row_affect, row_balance, row_memos, row_status) =
+                                rows.entry((transaction.date, transaction.id)).or_insert((
+                                    transaction,
+                                    Default::default(),
+                                    running_balance,
+                                    vec![],
+                                    status,
+                                ));
+                            *row_status = (*row_status).min(status);
+                            *row_affect += affect;
+                            *row_balance += affect;
+                            row_memos.extend(move_.memo);
                             let running_balance = *row_balance;
                             (rows, running_balance)
                         },
                     )
                     .0
                     .into_iter()
-                    .map(|(transaction_id, (transaction_date, affect, balance))| {
-                        [
-                            format!("{transaction_id} {transaction_date}").cell(),
-                            format!("{affect:+}").cell(),
-                            balance.cell(),
-                        ]
-                    }),
-                ["transaction", "affect", "balance"],
+                    .map(
+                        |(
+                            (date, transaction_id),
+                            (transaction, affect, balance, memos, status),
+                        )| {
+                            [
+                                format!("{transaction_id} {date}").cell(),
+                                format!("{affect:+}").cell(),
+                                balance.cell(),
+                                status.cell(),
+                                transaction.summary().cell(),
+                                memos.join("; ").cell(),
+                            ]
+                        },
+                    ),
+                [
+                    "transaction",
+                    "affect",
+                    "balance",
+                    "status",
+                    "description",
+                    "memo",
+                ],
             ),
             Report::TransactionShow { id } => {
                 let table = format_table(
-                    events.all_moves().filter_map(|move_| {
+                    events.recorded_moves().filter_map(|move_| {
                         if &move_.transaction == id {
-                            Some([
-                                move_.debit_account.cell(),
-                                move_.credit_account.cell(),
-                                format!("{} {}", move_.amount, move_.unit).cell(),
-                            ])
+                            Some(move_row(move_))
                         } else {
                             None
                         }
                     }),
-                    ["from", "to", "amount"],
+                    ["move", "from", "to", "amount", "memo"],
                 );
-
-                let transaction_date = events
+                let transaction = events
                     .get_transaction(id)
-                    .ok_or(Error::ReportTransactionShowTransactionNotFound(*id))?
-                    .date
-                    .format("%F");
-
-                format!("{transaction_date}\n{table}")
+                    .ok_or(Error::ReportTransactionShowTransactionNotFound(*id))?;
+                let heading = transaction_heading(&transaction);
+                format!("{heading}\n{table}")
+            }
+            Report::MoveShow { id } => {
+                let move_ = events
+                    .get_move(id)
+                    .ok_or(Error::ReportMoveShowMoveNotFound(*id))?;
+                let transaction = events.get_transaction(&move_.transaction).unwrap();
+                let heading = format!("{} {}", transaction.id, transaction_heading(&transaction));
+                let table =
+                    format_table([move_row(move_)], ["move", "from", "to", "amount", "memo"]);
+                format!("{heading}\n{table}")
+            }
+            Report::TransactionList { filter } => {
+                let moves = events.filtered_moves(filter);
+                let mut totals = BTreeMap::<unit::Name, Amount>::new();
+                let mut rows = Vec::<[String; 6]>::new();
+                for transaction in events
+                    .all_transactions()
+                    .into_values()
+                    .filter(|transaction| !transaction.voided && filter.matches(transaction))
+                {
+                    let transaction_moves = moves
+                        .iter()
+                        .filter(|move_| move_.transaction == transaction.id)
+                        .collect::<Vec<_>>();
+                    if transaction_moves.is_empty() && filter.is_move_specific() {
+                        continue;
+                    }
+                    let mut heading = [
+                        format!("{} {}", transaction.id, transaction.date),
+                        transaction.summary(),
+                        transaction.tags.iter().join(", "),
+                    ];
+                    if transaction_moves.is_empty() {
+                        rows.push(Default::default());
+                        rows.last_mut().unwrap()[..3].clone_from_slice(&heading);
+                    }
+                    for move_ in transaction_moves {
+                        *totals.entry(move_.unit.clone()).or_default() += move_.amount.into();
+                        let [transaction, description, tags] = std::mem::take(&mut heading);
+                        rows.push([
+                            transaction,
+                            description,
+                            tags,
+                            move_.debit_account.to_string(),
+                            move_.credit_account.to_string(),
+                            format!("{} {}", move_.amount, move_.unit),
+                        ]);
+                    }
+                }
+                rows.extend(totals.into_iter().map(|(unit, amount)| {
+                    [
+                        "total".to_string(),
+                        String::new(),
+                        String::new(),
+                        String::new(),
+                        String::new(),
+                        format!("{amount} {unit}"),
+                    ]
+                }));
+                format_table(
+                    rows.into_iter()
+                        .map(|[transaction, description, tags, from, to, amount]| {
+                            [
+                                transaction.cell(),
+                                description.cell(),
+                                tags.cell(),
+                                from.cell(),
+                                to.cell(),
+                                amount.cell().justify(cli_table::format::Justify::Right),
+                            ]
+                        }),
+                    ["transaction", "description", "tags", "from", "to", "amount"],
+                )
+            }
+            Report::ToBeBudgeted => format_table(
+                events.to_be_budgeted().into_iter().map(|(unit, amount)| {
+                    [
+                        amount
+                            .0
+                            .to_string()
+                            .cell()
+                            .justify(cli_table::format::Justify::Right),
+                        unit.0.cell(),
+                    ]
+                }),
+                ["to be budgeted", ""],
+            ),
+            Report::BudgetVsActual { month } => {
+                let units = events.all_units();
+                let table = format_table(
+                    events.budget_month(*month).into_iter().flat_map(
+                        |(envelope, envelope_units)| {
+                            let units = &units;
+                            envelope_units
+                                .into_iter()
+                                .map(move |(unit, envelope_month)| {
+                                    let scale = units[&unit].decimal_places as u32;
+                                    [
+                                        envelope.clone().cell(),
+                                        envelope_month
+                                            .carried_over
+                                            .rescaled(scale)
+                                            .cell()
+                                            .justify(cli_table::format::Justify::Right),
+                                        envelope_month
+                                            .allocated
+                                            .rescaled(scale)
+                                            .cell()
+                                            .justify(cli_table::format::Justify::Right),
+                                        format!("{:+}", envelope_month.activity.rescaled(scale))
+                                            .cell()
+                                            .justify(cli_table::format::Justify::Right),
+                                        envelope_month
+                                            .available
+                                            .rescaled(scale)
+                                            .cell()
+                                            .justify(cli_table::format::Justify::Right),
+                                        unit.cell(),
+                                    ]
+                                })
+                        },
+                    ),
+                    [
+                        "envelope",
+                        "carried",
+                        "allocated",
+                        "activity",
+                        "available",
+                        "",
+                    ],
+                );
+                format!("{month}\n{table}")
+            }
+            Report::Goals => format_table(
+                events
+                    .all_goal_progress()
+                    .into_iter()
+                    .map(|(envelope, progress)| {
+                        let goal = match &progress.goal {
+                            Goal::TargetBalance { amount, unit, by } => {
+                                format!("{amount} {unit} by {}", by.format("%F"))
+                            }
+                            Goal::MonthlyContribution { amount, unit } => {
+                                format!("{amount} {unit} monthly")
+                            }
+                        };
+                        [
+                            envelope.cell(),
+                            goal.cell(),
+                            format!("{}%", progress.percent)
+                                .cell()
+                                .justify(cli_table::format::Justify::Right),
+                            format!("{} {}", progress.needed_monthly, progress.goal.unit())
+                                .cell()
+                                .justify(cli_table::format::Justify::Right),
+                        ]
+                    }),
+                ["envelope", "goal", "progress", "needed monthly"],
+            ),
+            Report::Reconcile {
+                account,
+                statement_date,
+                statement_balance,
+                unit,
+            } => {
+                let transactions = events.all_transactions();
+                let scale = events
+                    .get_unit(unit)
+                    .map_or(0, |unit| unit.decimal_places as u32);
+                let table = format_table(
+                    events
+                        .all_moves()
+                        .filter(|move_| {
+                            move_.status(account) != move_::Status::Reconciled
+                                && &move_.unit == unit
+                                && [&move_.debit_account, &move_.credit_account].contains(&account)
+                                && transactions[&move_.transaction].date <= *statement_date
+                        })
+                        .map(|move_| {
+                            let transaction = &transactions[&move_.transaction];
+                            let affect = if &move_.debit_account == account {
+                                -Amount::from(move_.amount)
+                            } else {
+                                move_.amount.into()
+                            };
+                            [
+                                move_.id.cell(),
+                                format!("{} {}", transaction.id, transaction.date).cell(),
+                                move_.status(account).cell(),
+                                format!("{affect:+}")
+                                    .cell()
+                                    .justify(cli_table::format::Justify::Right),
+                            ]
+                        }),
+                    ["move", "transaction", "status", "affect"],
+                );
+                let cleared_balance = events
+                    .cleared_balance(account, unit, *statement_date)
+                    .rescaled(scale);
+                let difference = (*statement_balance - cleared_balance).rescaled(scale);
+                let outcome = if difference == Amount::default() {
+                    "reconciled".to_string()
+                } else {
+                    format!("difference {difference} {unit}")
+                };
+                format!(
+                    "{table}cleared balance {cleared_balance} {unit}, statement balance {statement_balance} {unit}: {outcome}\n"
+                )
+            }
+            Report::AccountList => {
+                let balances = events.all_signed_balances(&Default::default());
+                let moves = events.all_moves().collect::<Vec<_>>();
+                format_table(
+                    events.all_accounts().into_values().map(|account| {
+                        let move_count = moves
+                            .iter()
+                            .filter(|move_| {
+                                [&move_.debit_account, &move_.credit_account]
+                                    .contains(&&account.name)
+                            })
+                            .count();
+                        let balance = balances
+                            .get(&account.name)
+                            .map(format_balance)
+                            .unwrap_or_default();
+                        [
+                            account.name.cell(),
+                            account.kind.cell(),
+                            account.order.cell(),
+                            move_count.cell(),
+                            balance.cell(),
+                        ]
+                    }),
+                    ["account", "kind", "created", "moves", "balance"],
+                )
+            }
+            Report::AccountShow { name, recent } => {
+                let account = events
+                    .get_account(name)
+                    .ok_or_else(|| Error::ReportAccountShowAccountNotFound(name.clone()))?;
+                let transactions = events.all_transactions();
+                let moves = events
+                    .all_moves()
+                    .filter(|move_| [&move_.debit_account, &move_.credit_account].contains(&name))
+                    .sorted_by_key(|move_| (transactions[&move_.transaction].date, move_.id))
+                    .collect::<Vec<_>>();
+                let balance = events
+                    .all_signed_balances(&Default::default())
+                    .get(name)
+                    .map(format_balance)
+                    .unwrap_or_default();
+                let account::Metadata {
+                    opened,
+                    institution,
+                    number_suffix,
+                    notes,
+                } = &account.metadata;
+                let mut details = vec![
+                    format!("kind: {}", account.kind),
+                    format!("created: {}", account.order),
+                ];
+                details.extend(opened.map(|date| format!("opened: {date}")));
+                details.extend(account.closed.map(|date| format!("closed: {date}")));
+                details.extend(
+                    institution
+                        .as_ref()
+                        .map(|institution| format!("institution: {institution}")),
+                );
+                details.extend(
+                    number_suffix
+                        .as_ref()
+                        .map(|suffix| format!("number: ending in {suffix}")),
+                );
+                details.extend(notes.as_ref().map(|notes| format!("notes: {notes}")));
+                details.push(format!("balance: {balance}"));
+                details.push(format!("moves: {}", moves.len()));
+                let activity = format_table(
+                    moves[moves.len().saturating_sub(*recent)..]
+                        .iter()
+                        .map(|move_| {
+                            let transaction = &transactions[&move_.transaction];
+                            let (other_account, affect) = if &move_.debit_account == name {
+                                (&move_.credit_account, -Amount::from(move_.amount))
+                            } else {
+                                (&move_.debit_account, move_.amount.into())
+                            };
+                            let affect = account.kind.signed(affect);
+                            [
+                                format!("{} {}", transaction.id, transaction.date).cell(),
+                                move_.id.cell(),
+                                other_account.cell(),
+                                format!("{affect:+} {}", move_.unit)
+                                    .cell()
+                                    .justify(cli_table::format::Justify::Right),
+                            ]
+                        }),
+                    ["transaction", "move", "account", "affect"],
+                );
+                format!("{name}\n{}\n{activity}", details.join("\n"))
+            }
+            Report::History {
+                types,
+                since,
+                until,
+            } => events
+                .recorded()
+                .iter()
+                .enumerate()
+                .filter(|(_index, Record { origin, event })| {
+                    let recorded_at = origin
+                        .recorded_at
+                        .map(|recorded_at| recorded_at.naive_local());
+                    (types.is_empty() || types.iter().any(|type_| type_ == event.type_name()))
+                        && since.is_none_or(|since| recorded_at.is_some_and(|at| at >= since))
+                        && until.is_none_or(|until| recorded_at.is_some_and(|at| at <= until))
+                })
+                .map(|(index, Record { origin, event })| {
+                    let number = index + 1;
+                    let mut lines = vec![format!(
+                        "#{number} {}: {}",
+                        event.type_name(),
+                        describe_event(event)
+                    )];
+                    lines.extend(describe_origin(origin));
+                    lines.join("\n") + "\n"
+                })
+                .collect(),
+            Report::Check { strict } => {
+                let findings = events.findings();
+                let found = !findings.is_empty();
+                let table = format_table(
+                    findings
+                        .into_iter()
+                        .map(|Finding { transaction, kind }| [transaction.cell(), kind.cell()]),
+                    ["transaction", "finding"],
+                );
+                if *strict && found {
+                    return Err(Error::ReportCheckFindings(table));
+                }
+                table
             }
         };
         // TODO perhaps if we use the table crate to print, it would detect TTY
@@ -125,6 +625,70 @@ impl Report {
     }
 }
 
+/// Warnings for each of the last `added` moves, against the events before it was added
+fn overspending_warnings(events: &Events, added: usize) -> String {
+    let units = events.all_units();
+    let moves = events.all_moves().collect::<Vec<_>>();
+    moves[moves.len() - added..]
+        .iter()
+        .enumerate()
+        .filter_map(|(index, move_)| {
+            events
+                .prefix(events.len() - added + index)
+                .overspending(move_)
+        })
+        .map(
+            |Overspending {
+                 envelope,
+                 unit,
+                 before,
+                 after,
+             }| {
+                let scale = units[&unit].decimal_places as u32;
+                let before = before.rescaled(scale);
+                format!(
+                    "Warning: envelope {envelope} overspent: {before} {unit} before, {after} {unit} after\n"
+                )
+            },
+        )
+        .collect()
+}
+
+/// The date of a transaction, followed by its summary and whether it is void
+fn transaction_heading(transaction: &Transaction) -> String {
+    let transaction_date = transaction.date.format("%F");
+    let summary = transaction.summary();
+    let heading = if summary.is_empty() {
+        transaction_date.to_string()
+    } else {
+        format!("{transaction_date} {summary}")
+    };
+    if transaction.voided {
+        format!("{heading} (void)")
+    } else {
+        heading
+    }
+}
+
+fn move_row(move_: Move) -> impl Row {
+    [
+        move_.id.cell(),
+        move_.debit_account.cell(),
+        move_.credit_account.cell(),
+        format!("{} {}", move_.amount, move_.unit).cell(),
+        move_.memo.unwrap_or_default().cell(),
+    ]
+}
+
+/// Amounts of every unit, as `1.00 USD, 2.00 EUR`
+fn format_balance(balance: &Balance) -> String {
+    balance
+        .0
+        .iter()
+        .map(|(unit, amount)| format!("{amount} {unit}"))
+        .join(", ")
+}
+
 fn format_table(rows: impl IntoIterator<Item = impl Row>, titles: impl Row) -> String {
     let table_border = cli_table::format::Border::builder().build();
     let table_separator = cli_table::format::Separator::builder().build();
@@ -136,3 +700,140 @@ fn format_table(rows: impl IntoIterator<Item = impl Row>, titles: impl Row) -> S
         .unwrap()
         .to_string()
 }
+
+/// When and by whom an event was recorded, then the command that recorded it, as far as known
+fn describe_origin(
+    Origin {
+        recorded_at,
+        user,
+        arguments,
+    }: &Origin,
+) -> Vec<String> {
+    let recorded = match (recorded_at, user) {
+        (Some(recorded_at), Some(user)) => Some(format!(
+            "recorded {} by {user}",
+            recorded_at.format("%F %T")
+        )),
+        (Some(recorded_at), None) => Some(format!("recorded {}", recorded_at.format("%F %T"))),
+        (None, Some(user)) => Some(format!("recorded by {user}")),
+        (None, None) => None,
+    };
+    let command = arguments.as_ref().map(|arguments| {
+        [env!("CARGO_PKG_NAME").to_owned()]
+            .into_iter()
+            .chain(arguments.iter().map(|argument| {
+                if argument.is_empty() || argument.contains(char::is_whitespace) {
+                    format!("{argument:?}")
+                } else {
+                    argument.clone()
+                }
+            }))
+            .join(" ")
+    });
+    recorded
+        .into_iter()
+        .chain(command)
+        .map(|line| format!("  {line}"))
+        .collect()
+}
+
+fn describe_event(event: &Event) -> String {
+    match event {
+        Event::AccountCreated(events::AccountCreated { name, kind, .. }) => {
+            format!("{kind} account {name}")
+        }
+        Event::AccountRenamed(events::AccountRenamed { from, to }) => format!("{from} to {to}"),
+        Event::AccountClosed(events::AccountClosed { name, date }) => {
+            format!("{name} on {}", date.format("%F"))
+        }
+        Event::AccountMetadataSet(events::AccountMetadataSet { name, .. }) => name.to_string(),
+        Event::TransactionRecorded(events::TransactionRecorded {
+            date,
+            description,
+            payee,
+        }) => [
+            Some(date.format("%F").to_string()),
+            payee.clone(),
+            description.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .join(" "),
+        Event::UnitCreated(events::UnitCreated {
+            name,
+            decimal_places,
+        }) => format!("{name} with {decimal_places} decimal places"),
+        Event::MoveAdded(events::MoveAdded {
+            transaction,
+            debit_account,
+            credit_account,
+            amount,
+            unit,
+            ..
+        }) => format!("{debit_account}->{credit_account} {amount} {unit} to {transaction}"),
+        Event::MoveEdited(events::MoveEdited {
+            move_,
+            debit_account,
+            credit_account,
+            amount,
+            unit,
+            ..
+        }) => format!("{move_} to {debit_account}->{credit_account} {amount} {unit}"),
+        Event::MoveRemoved(events::MoveRemoved { move_ }) => move_.to_string(),
+        Event::MoveCleared(events::MoveCleared { move_, account, .. }) => {
+            format!("{move_} for {account}")
+        }
+        Event::FundsAllocated(events::FundsAllocated {
+            envelope,
+            amount,
+            unit,
+            month,
+        }) => format!("{amount} {unit} to {envelope} for {month}"),
+        Event::FundsReallocated(events::FundsReallocated {
+            from_envelope,
+            to_envelope,
+            amount,
+            unit,
+            month,
+        }) => format!("{amount} {unit} from {from_envelope} to {to_envelope} for {month}"),
+        Event::RolloverPolicySet(events::RolloverPolicySet { envelope, policy }) => {
+            format!("{envelope} to {policy}")
+        }
+        Event::GoalSet(events::GoalSet { envelope, goal }) => match goal {
+            Goal::TargetBalance { amount, unit, by } => {
+                format!("{envelope} to {amount} {unit} by {}", by.format("%F"))
+            }
+            Goal::MonthlyContribution { amount, unit } => {
+                format!("{envelope} to {amount} {unit} monthly")
+            }
+        },
+        Event::OverspendingPolicySet(events::OverspendingPolicySet { policy }) => {
+            policy.to_string()
+        }
+        Event::TransactionTagged(events::TransactionTagged { transaction, tag })
+        | Event::TransactionUntagged(events::TransactionUntagged { transaction, tag }) => {
+            format!("{transaction} with {tag}")
+        }
+        Event::TransactionVoided(events::TransactionVoided { transaction }) => {
+            transaction.to_string()
+        }
+        Event::TransactionDateSet(events::TransactionDateSet { transaction, date }) => {
+            format!("{transaction} to {}", date.format("%F"))
+        }
+        Event::BalanceAsserted(events::BalanceAsserted {
+            account,
+            date,
+            amount,
+            unit,
+        }) => format!("{account} at {amount} {unit} on {}", date.format("%F")),
+        Event::Reconciled(events::Reconciled {
+            account,
+            statement_date,
+            statement_balance,
+            unit,
+        }) => format!(
+            "{account} at {statement_balance} {unit} on {}",
+            statement_date.format("%F")
+        ),
+    }
+}
@@ -1,40 +1,70 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::{
     entities::{
         account::{self, Account},
+        activity::Activity,
+        allocation::Allocation,
+        amount::Amount,
+        assertion::{self, Assertion},
         balance::Balance,
-        move_::Move,
+        envelope_month::EnvelopeMonth,
+        filter::Filter,
+        finding::{self, Finding},
+        goal::{self, Goal},
+        month::Month,
+        move_::{self, Move},
+        overspending::{self, Overspending},
+        rollover,
         transaction::{self, Transaction},
         unit::{self, Unit},
     },
     events::{self, Event, Events},
 };
+use chrono::NaiveDate;
+use rust_decimal::{Decimal, RoundingStrategy};
 
 impl Events {
     pub(crate) fn all_account_names(&self) -> Vec<account::Name> {
         self.all_accounts().keys().cloned().collect()
     }
     pub(crate) fn all_accounts(&self) -> BTreeMap<account::Name, Account> {
-        self.iter()
-            .filter_map(|event| match event {
-                Event::AccountCreated(account_created) => Some(account_created),
-                _ => None,
-            })
-            .cloned()
-            .fold(
-                BTreeMap::new(),
-                |mut accounts, events::AccountCreated { name, kind }| {
+        self.iter().fold(BTreeMap::new(), |mut accounts, event| {
+            match event {
+                Event::AccountCreated(events::AccountCreated {
+                    name,
+                    kind,
+                    metadata,
+                }) => {
+                    let order = accounts.len() + 1;
                     accounts.insert(
                         name.clone(),
                         Account {
-                            _kind: kind,
-                            _name: name,
+                            kind: *kind,
+                            name: name.clone(),
+                            order,
+                            closed: None,
+                            metadata: metadata.clone(),
                         },
                     );
-                    accounts
-                },
-            )
+                }
+                Event::AccountMetadataSet(events::AccountMetadataSet { name, metadata }) => {
+                    if let Some(account) = accounts.get_mut(name) {
+                        account.metadata = metadata.clone();
+                    }
+                }
+                Event::AccountClosed(events::AccountClosed { name, date }) => {
+                    if let Some(account) = accounts.get_mut(name) {
+                        account.closed = Some(*date);
+                    }
+                }
+                _ => {}
+            }
+            accounts
+        })
+    }
+    pub(crate) fn get_account(&self, account_name: &account::Name) -> Option<Account> {
+        self.all_accounts().get(account_name).cloned()
     }
     pub(crate) fn all_unit_names(&self) -> Vec<unit::Name> {
         self.all_units().keys().cloned().collect()
@@ -73,51 +103,221 @@ impl Events {
             .map(|id| transaction::Id(id as u64))
             .collect()
     }
+    pub(crate) fn next_transaction_id(&self) -> transaction::Id {
+        transaction::Id(self.all_transaction_ids().len() as u64 + 1)
+    }
     pub(crate) fn get_unit(&self, unit_name: &unit::Name) -> Option<Unit> {
         self.all_units().get(unit_name).cloned()
     }
-    pub(crate) fn all_moves(&'_ self) -> impl Iterator<Item = Move> + '_ {
-        self.iter().filter_map(|event| match event {
-            Event::MoveAdded(events::MoveAdded {
-                debit_account,
-                credit_account,
-                amount,
-                unit,
-                transaction,
-            }) => Some(Move {
-                transaction: *transaction,
-                debit_account: debit_account.clone(),
-                credit_account: credit_account.clone(),
-                amount: *amount,
-                unit: unit.clone(),
-            }),
-            _ => None,
-        })
+    /// Moves of transactions that have not been voided.
+    pub(crate) fn all_moves(&self) -> impl Iterator<Item = Move> {
+        let voided = self
+            .all_transactions()
+            .into_values()
+            .filter(|transaction| transaction.voided)
+            .map(|transaction| transaction.id)
+            .collect::<BTreeSet<_>>();
+        self.recorded_moves()
+            .filter(move |move_| !voided.contains(&move_.transaction))
     }
-    pub(crate) fn get_transaction(&self, transaction_id: &transaction::Id) -> Option<Transaction> {
+    /// Every move ever added, including those of voided transactions,
+    /// with edits applied and removed moves left out.
+    pub(crate) fn recorded_moves(&self) -> impl Iterator<Item = Move> {
         self.iter()
-            .filter_map(|event| {
-                if let Event::TransactionRecorded(transaction_recorded) = event {
-                    Some(transaction_recorded)
+            .fold(
+                (BTreeMap::new(), move_::Id(1), BTreeMap::new()),
+                |(mut moves, next_id, mut dates), event| match event {
+                    Event::TransactionRecorded(events::TransactionRecorded { date, .. }) => {
+                        dates.insert(transaction::Id(dates.len() as u64 + 1), *date);
+                        (moves, next_id, dates)
+                    }
+                    Event::TransactionDateSet(events::TransactionDateSet { transaction, date }) => {
+                        dates.insert(*transaction, *date);
+                        (moves, next_id, dates)
+                    }
+                    Event::MoveAdded(events::MoveAdded {
+                        debit_account,
+                        credit_account,
+                        amount,
+                        unit,
+                        transaction,
+                        memo,
+                    }) => {
+                        moves.insert(
+                            next_id,
+                            Move {
+                                id: next_id,
+                                transaction: *transaction,
+                                debit_account: debit_account.clone(),
+                                credit_account: credit_account.clone(),
+                                amount: *amount,
+                                unit: unit.clone(),
+                                memo: memo.clone(),
+                                debit_status: move_::Status::Pending,
+                                credit_status: move_::Status::Pending,
+                            },
+                        );
+                        (moves, move_::Id(next_id.0 + 1), dates)
+                    }
+                    Event::MoveEdited(events::MoveEdited {
+                        move_,
+                        debit_account,
+                        credit_account,
+                        amount,
+                        unit,
+                        memo,
+                    }) => {
+                        if let Some(move_) = moves.get_mut(move_) {
+                            move_.debit_account = debit_account.clone();
+                            move_.credit_account = credit_account.clone();
+                            move_.amount = *amount;
+                            move_.unit = unit.clone();
+                            move_.memo = memo.clone();
+                        }
+                        (moves, next_id, dates)
+                    }
+                    Event::MoveRemoved(events::MoveRemoved { move_ }) => {
+                        moves.remove(move_);
+                        (moves, next_id, dates)
+                    }
+                    Event::MoveCleared(events::MoveCleared { move_, account, .. }) => {
+                        if let Some(move_) = moves.get_mut(move_) {
+                            if account == &move_.debit_account {
+                                move_.debit_status = move_::Status::Cleared;
+                            }
+                            if account == &move_.credit_account {
+                                move_.credit_status = move_::Status::Cleared;
+                            }
+                        }
+                        (moves, next_id, dates)
+                    }
+                    Event::Reconciled(events::Reconciled {
+                        account,
+                        statement_date,
+                        unit,
+                        ..
+                    }) => {
+                        for move_ in moves.values_mut().filter(|move_| {
+                            &move_.unit == unit && dates[&move_.transaction] <= *statement_date
+                        }) {
+                            if &move_.debit_account == account
+                                && move_.debit_status == move_::Status::Cleared
+                            {
+                                move_.debit_status = move_::Status::Reconciled;
+                            }
+                            if &move_.credit_account == account
+                                && move_.credit_status == move_::Status::Cleared
+                            {
+                                move_.credit_status = move_::Status::Reconciled;
+                            }
+                        }
+                        (moves, next_id, dates)
+                    }
+                    _ => (moves, next_id, dates),
+                },
+            )
+            .0
+            .into_values()
+    }
+    pub(crate) fn get_move(&self, move_id: &move_::Id) -> Option<Move> {
+        self.recorded_moves().find(|move_| &move_.id == move_id)
+    }
+    pub(crate) fn next_move_id(&self) -> move_::Id {
+        let added = self
+            .iter()
+            .filter(|event| matches!(event, Event::MoveAdded(_)))
+            .count();
+        move_::Id(added as u64 + 1)
+    }
+    /// The balance of an account as of the end of a statement date,
+    /// counting only moves cleared or reconciled for that account
+    pub(crate) fn cleared_balance(
+        &self,
+        account: &account::Name,
+        unit: &unit::Name,
+        statement_date: NaiveDate,
+    ) -> Amount {
+        let transactions = self.all_transactions();
+        self.all_moves()
+            .filter(|move_| {
+                move_.status(account) != move_::Status::Pending
+                    && &move_.unit == unit
+                    && transactions[&move_.transaction].date <= statement_date
+            })
+            .fold(Amount::default(), |balance, move_| {
+                if &move_.debit_account == account {
+                    balance - move_.amount.into()
+                } else if &move_.credit_account == account {
+                    balance + move_.amount.into()
                 } else {
-                    None
+                    balance
                 }
             })
-            .enumerate()
-            .find_map(|(index, transaction_created)| {
-                let current_id = transaction::Id(index as u64 + 1);
-                if &current_id == transaction_id {
-                    Some(Transaction {
-                        id: current_id,
-                        date: transaction_created.date,
-                    })
-                } else {
-                    None
+    }
+    pub(crate) fn all_transactions(&self) -> BTreeMap<transaction::Id, Transaction> {
+        self.iter()
+            .fold(BTreeMap::new(), |mut transactions, event| {
+                match event {
+                    Event::TransactionRecorded(events::TransactionRecorded {
+                        date,
+                        description,
+                        payee,
+                    }) => {
+                        let id = transaction::Id(transactions.len() as u64 + 1);
+                        transactions.insert(
+                            id,
+                            Transaction {
+                                id,
+                                date: *date,
+                                description: description.clone(),
+                                payee: payee.clone(),
+                                tags: BTreeSet::new(),
+                                voided: false,
+                            },
+                        );
+                    }
+                    Event::TransactionTagged(events::TransactionTagged { transaction, tag }) => {
+                        if let Some(transaction) = transactions.get_mut(transaction) {
+                            transaction.tags.insert(tag.clone());
+                        }
+                    }
+                    Event::TransactionUntagged(events::TransactionUntagged {
+                        transaction,
+                        tag,
+                    }) => {
+                        if let Some(transaction) = transactions.get_mut(transaction) {
+                            transaction.tags.remove(tag);
+                        }
+                    }
+                    Event::TransactionDateSet(events::TransactionDateSet { transaction, date }) => {
+                        if let Some(transaction) = transactions.get_mut(transaction) {
+                            transaction.date = *date;
+                        }
+                    }
+                    Event::TransactionVoided(events::TransactionVoided { transaction }) => {
+                        if let Some(transaction) = transactions.get_mut(transaction) {
+                            transaction.voided = true;
+                        }
+                    }
+                    _ => {}
                 }
+                transactions
             })
     }
-    pub(crate) fn all_balances(&self) -> BTreeMap<account::Name, Balance> {
+    pub(crate) fn get_transaction(&self, transaction_id: &transaction::Id) -> Option<Transaction> {
+        self.all_transactions().get(transaction_id).cloned()
+    }
+    pub(crate) fn filtered_moves(&self, filter: &Filter) -> Vec<Move> {
+        let transactions = self.all_transactions();
         self.all_moves()
+            .filter(|move_| {
+                filter.matches(&transactions[&move_.transaction]) && filter.matches_move(move_)
+            })
+            .collect()
+    }
+    pub(crate) fn all_balances(&self, filter: &Filter) -> BTreeMap<account::Name, Balance> {
+        self.filtered_moves(filter)
+            .into_iter()
             .fold(BTreeMap::new(), |mut balances, move_| {
                 let balance = balances.entry(move_.debit_account).or_default();
                 let unit_amount = balance.0.entry(move_.unit.clone()).or_default();
@@ -130,7 +330,619 @@ impl Events {
                 balances
             })
     }
+    /// Balances of every account and every ancestor,
+    /// each including the balances of its descendants,
+    /// signed according to the kind of each account
+    pub(crate) fn rolled_up_balances(&self, filter: &Filter) -> BTreeMap<account::Name, Balance> {
+        let mut rolled_up = BTreeMap::<account::Name, Balance>::new();
+        for (account, balance) in self.all_signed_balances(filter) {
+            for name in account.ancestors().into_iter().chain([account]) {
+                let rolled_up_balance = rolled_up.entry(name).or_default();
+                for (unit, amount) in &balance.0 {
+                    *rolled_up_balance.0.entry(unit.clone()).or_default() += *amount;
+                }
+            }
+        }
+        rolled_up
+    }
+    /// Balances signed according to the kind of each account
+    pub(crate) fn all_signed_balances(&self, filter: &Filter) -> BTreeMap<account::Name, Balance> {
+        let accounts = self.all_accounts();
+        self.all_balances(filter)
+            .into_iter()
+            .map(|(name, mut balance)| {
+                if let Some(account) = accounts.get(&name) {
+                    for amount in balance.0.values_mut() {
+                        *amount = account.kind.signed(*amount);
+                    }
+                }
+                (name, balance)
+            })
+            .collect()
+    }
+    /// Sums of the balances of budget, asset and liability accounts
+    pub(crate) fn net_worth(&self, filter: &Filter) -> BTreeMap<unit::Name, Amount> {
+        let accounts = self.all_accounts();
+        self.all_balances(filter)
+            .into_iter()
+            .filter(|(name, _balance)| {
+                accounts
+                    .get(name)
+                    .is_some_and(|account| account.kind.is_net_worth())
+            })
+            .flat_map(|(_name, balance)| balance.0)
+            .fold(BTreeMap::new(), |mut net_worth, (unit, amount)| {
+                *net_worth.entry(unit).or_default() += amount;
+                net_worth
+            })
+    }
     pub(crate) fn last_transaction_id(&self) -> transaction::Id {
         *self.all_transaction_ids().last().unwrap()
     }
+    pub(crate) fn findings(&self) -> Vec<Finding> {
+        let accounts = self.all_accounts();
+        let transactions = self.all_transactions();
+        let moves = self.all_moves().fold(
+            BTreeMap::<transaction::Id, Vec<Move>>::new(),
+            |mut moves, move_| {
+                moves.entry(move_.transaction).or_default().push(move_);
+                moves
+            },
+        );
+        self.all_transaction_ids()
+            .into_iter()
+            .filter(|id| !transactions[id].voided)
+            .flat_map(|transaction| {
+                let moves = moves
+                    .get(&transaction)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                let mut kinds = vec![];
+                if moves.is_empty() {
+                    kinds.push(finding::Kind::NoMoves);
+                } else if !moves.iter().any(|move_| {
+                    is_budget(&accounts, &move_.debit_account)
+                        || is_budget(&accounts, &move_.credit_account)
+                }) {
+                    kinds.push(finding::Kind::NoBudgetAccount);
+                }
+                let legs_per_unit =
+                    moves
+                        .iter()
+                        .fold(BTreeMap::<&unit::Name, usize>::new(), |mut legs, move_| {
+                            *legs.entry(&move_.unit).or_default() += 1;
+                            legs
+                        });
+                if legs_per_unit.len() > 1 {
+                    kinds.extend(
+                        legs_per_unit
+                            .into_iter()
+                            .filter(|(_unit, legs)| *legs == 1)
+                            .map(|(unit, _legs)| finding::Kind::SingleLegUnit(unit.clone())),
+                    );
+                }
+                kinds
+                    .into_iter()
+                    .map(move |kind| Finding { transaction, kind })
+            })
+            .collect()
+    }
+    pub(crate) fn all_balance_assertions(&self) -> Vec<Assertion> {
+        self.iter()
+            .filter_map(|event| match event {
+                Event::BalanceAsserted(events::BalanceAsserted {
+                    account,
+                    date,
+                    amount,
+                    unit,
+                }) => Some(Assertion {
+                    account: account.clone(),
+                    date: *date,
+                    amount: *amount,
+                    unit: unit.clone(),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+    pub(crate) fn balance_assertion_failure(
+        &self,
+        assertion: Assertion,
+    ) -> Option<assertion::Failure> {
+        let actual = self
+            .all_balances(&Filter {
+                until: Some(assertion.date),
+                ..Default::default()
+            })
+            .get(&assertion.account)
+            .and_then(|balance| balance.0.get(&assertion.unit))
+            .copied()
+            .unwrap_or_default();
+        if actual == assertion.amount {
+            None
+        } else {
+            Some(assertion::Failure { assertion, actual })
+        }
+    }
+    /// The accounts whose balances appending the event can change,
+    /// each with the earliest date it can change them from
+    pub(crate) fn balance_changes(&self, event: &Event) -> Vec<(account::Name, NaiveDate)> {
+        let transactions = self.all_transactions();
+        let date_of = |transaction: &transaction::Id| {
+            transactions
+                .get(transaction)
+                .map(|transaction| transaction.date)
+        };
+        let accounts_of = |transaction: &transaction::Id| {
+            self.recorded_moves()
+                .filter(|move_| &move_.transaction == transaction)
+                .flat_map(|move_| [move_.debit_account, move_.credit_account])
+                .collect::<Vec<_>>()
+        };
+        let (accounts, date) = match event {
+            Event::MoveAdded(events::MoveAdded {
+                transaction,
+                debit_account,
+                credit_account,
+                ..
+            }) => (
+                vec![debit_account.clone(), credit_account.clone()],
+                date_of(transaction),
+            ),
+            Event::MoveEdited(events::MoveEdited {
+                move_,
+                debit_account,
+                credit_account,
+                ..
+            }) => match self.get_move(move_) {
+                Some(found) => (
+                    vec![
+                        found.debit_account,
+                        found.credit_account,
+                        debit_account.clone(),
+                        credit_account.clone(),
+                    ],
+                    date_of(&found.transaction),
+                ),
+                None => return vec![],
+            },
+            Event::MoveRemoved(events::MoveRemoved { move_ }) => match self.get_move(move_) {
+                Some(found) => (
+                    vec![found.debit_account, found.credit_account],
+                    date_of(&found.transaction),
+                ),
+                None => return vec![],
+            },
+            Event::TransactionVoided(events::TransactionVoided { transaction }) => {
+                (accounts_of(transaction), date_of(transaction))
+            }
+            Event::TransactionDateSet(events::TransactionDateSet { transaction, date }) => (
+                accounts_of(transaction),
+                date_of(transaction).map(|previous| previous.min(*date)),
+            ),
+            Event::BalanceAsserted(events::BalanceAsserted { account, date, .. }) => {
+                (vec![account.clone()], Some(*date))
+            }
+            _ => return vec![],
+        };
+        date.map(|date| {
+            accounts
+                .into_iter()
+                .map(|account| (account, date))
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+    pub(crate) fn all_allocations(&'_ self) -> impl Iterator<Item = Allocation> + '_ {
+        self.iter().flat_map(|event| match event {
+            Event::FundsAllocated(events::FundsAllocated {
+                envelope,
+                amount,
+                unit,
+                month,
+            }) => vec![Allocation {
+                envelope: envelope.clone(),
+                amount: (*amount).into(),
+                unit: unit.clone(),
+                month: *month,
+            }],
+            Event::FundsReallocated(events::FundsReallocated {
+                from_envelope,
+                to_envelope,
+                amount,
+                unit,
+                month,
+            }) => vec![
+                Allocation {
+                    envelope: from_envelope.clone(),
+                    amount: -Amount::from(*amount),
+                    unit: unit.clone(),
+                    month: *month,
+                },
+                Allocation {
+                    envelope: to_envelope.clone(),
+                    amount: (*amount).into(),
+                    unit: unit.clone(),
+                    month: *month,
+                },
+            ],
+            _ => vec![],
+        })
+    }
+    pub(crate) fn all_envelope_activity(&self) -> Vec<Activity> {
+        let accounts = self.all_accounts();
+        let transactions = self.all_transactions();
+        self.all_moves()
+            .flat_map(|move_| {
+                let date = transactions[&move_.transaction].date;
+                envelope_changes(&accounts, &move_)
+                    .into_iter()
+                    .map(|(envelope, amount)| Activity {
+                        date,
+                        envelope,
+                        amount,
+                        unit: move_.unit.clone(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+    pub(crate) fn overspending_policy(&self) -> overspending::Policy {
+        self.iter()
+            .rev()
+            .find_map(|event| match event {
+                Event::OverspendingPolicySet(events::OverspendingPolicySet { policy }) => {
+                    Some(*policy)
+                }
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+    /// The budget account that appending the move would leave with a negative balance.
+    pub(crate) fn overspending(&self, move_: &Move) -> Option<Overspending> {
+        if !is_budget(&self.all_accounts(), &move_.debit_account) {
+            return None;
+        }
+        let before = self
+            .all_balances(&Default::default())
+            .get(&move_.debit_account)
+            .and_then(|balance| balance.0.get(&move_.unit))
+            .copied()
+            .unwrap_or_default();
+        let after = before - move_.amount.into();
+        (after < Amount::default()).then(|| Overspending {
+            envelope: move_.debit_account.clone(),
+            unit: move_.unit.clone(),
+            before,
+            after,
+        })
+    }
+    /// The policies of each envelope by the month they take effect in,
+    /// the current month when they were set, or `None` if none had begun
+    pub(crate) fn all_rollover_policies(
+        &self,
+    ) -> BTreeMap<account::Name, BTreeMap<Option<Month>, rollover::Policy>> {
+        self.iter()
+            .enumerate()
+            .filter_map(|(index, event)| match event {
+                Event::RolloverPolicySet(events::RolloverPolicySet { envelope, policy }) => {
+                    Some((envelope, self.prefix(index).current_month(), *policy))
+                }
+                _ => None,
+            })
+            .fold(
+                BTreeMap::new(),
+                |mut policies, (envelope, month, policy)| {
+                    policies
+                        .entry(envelope.clone())
+                        .or_insert_with(BTreeMap::new)
+                        .insert(month, policy);
+                    policies
+                },
+            )
+    }
+    /// A month ends once a move dated in a later month is recorded.
+    /// Allocating ahead for a later month, recording a transaction without moves
+    /// or voiding one does not end it.
+    pub(crate) fn current_month(&self) -> Option<Month> {
+        let transactions = self.all_transactions();
+        self.all_moves()
+            .map(|move_| Month::of(transactions[&move_.transaction].date))
+            .max()
+    }
+    pub(crate) fn to_be_budgeted(&self) -> BTreeMap<unit::Name, Amount> {
+        let accounts = self.all_accounts();
+        let income = self
+            .all_moves()
+            .filter(|move_| {
+                !is_budget(&accounts, &move_.debit_account)
+                    && is_budget(&accounts, &move_.credit_account)
+            })
+            .map(|move_| (move_.unit, Amount::from(move_.amount)));
+        let allocated = self
+            .all_allocations()
+            .map(|allocation| (allocation.unit, -allocation.amount));
+        let returned = self
+            .current_month()
+            .map(|month| self.envelope_months(month))
+            .into_iter()
+            .flatten()
+            .flat_map(|(_envelope, units)| {
+                units
+                    .into_iter()
+                    .map(|(unit, (_envelope_month, returned))| (unit, returned))
+            });
+        income
+            .chain(allocated)
+            .chain(returned)
+            .fold(BTreeMap::new(), |mut pool, (unit, amount)| {
+                *pool.entry(unit).or_default() += amount;
+                pool
+            })
+    }
+    pub(crate) fn all_envelope_balances(&self) -> BTreeMap<account::Name, Balance> {
+        let Some(month) = self.current_month() else {
+            return BTreeMap::new();
+        };
+        self.budget_month(month)
+            .into_iter()
+            .map(|(envelope, units)| {
+                let balance = units
+                    .into_iter()
+                    .map(|(unit, envelope_month)| (unit, envelope_month.available))
+                    .collect();
+                (envelope, Balance(balance))
+            })
+            .collect()
+    }
+    pub(crate) fn budget_month(
+        &self,
+        month: Month,
+    ) -> BTreeMap<account::Name, BTreeMap<unit::Name, EnvelopeMonth>> {
+        self.envelope_months(month)
+            .into_iter()
+            .map(|(envelope, units)| {
+                let units = units
+                    .into_iter()
+                    .map(|(unit, (envelope_month, _returned))| (unit, envelope_month))
+                    .collect();
+                (envelope, units)
+            })
+            .collect()
+    }
+    pub(crate) fn all_goals(&self) -> BTreeMap<account::Name, Goal> {
+        self.iter()
+            .filter_map(|event| match event {
+                Event::GoalSet(events::GoalSet { envelope, goal }) => {
+                    Some((envelope.clone(), goal.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+    /// Target balances are measured against what is available in the envelope now,
+    /// spreading what is missing over the months left through the target date.
+    /// Monthly contributions are measured against what was allocated in the current month.
+    pub(crate) fn all_goal_progress(&self) -> BTreeMap<account::Name, goal::Progress> {
+        let balances = self.all_envelope_balances();
+        let current_month = self.current_month();
+        let this_month = current_month
+            .map(|month| self.budget_month(month))
+            .unwrap_or_default();
+        self.all_goals()
+            .into_iter()
+            .map(|(envelope, goal)| {
+                let target = Amount::from(goal.amount());
+                let (achieved, months_left) = match &goal {
+                    Goal::TargetBalance { unit, by, .. } => {
+                        let available = balances
+                            .get(&envelope)
+                            .and_then(|balance| balance.0.get(unit))
+                            .copied()
+                            .unwrap_or_default();
+                        let months_left = current_month
+                            .map_or(1, |month| month.months_until(Month::of(*by)) + 1)
+                            .max(1);
+                        (available, months_left)
+                    }
+                    Goal::MonthlyContribution { unit, .. } => {
+                        let allocated = this_month
+                            .get(&envelope)
+                            .and_then(|units| units.get(unit))
+                            .map(|envelope_month| envelope_month.allocated)
+                            .unwrap_or_default();
+                        (allocated, 1)
+                    }
+                };
+                let missing = (target - achieved).max(Amount::default());
+                let needed_monthly = Amount(
+                    (missing.0 / Decimal::from(months_left)).round_dp_with_strategy(
+                        goal.amount().scale(),
+                        RoundingStrategy::AwayFromZero,
+                    ),
+                );
+                let percent = if target.0.is_zero() {
+                    Decimal::ONE_HUNDRED
+                } else {
+                    (achieved.0 / target.0 * Decimal::ONE_HUNDRED)
+                        .clamp(Decimal::ZERO, Decimal::ONE_HUNDRED)
+                        .round_dp(0)
+                };
+                (
+                    envelope,
+                    goal::Progress {
+                        goal,
+                        percent,
+                        needed_monthly,
+                    },
+                )
+            })
+            .collect()
+    }
+    /// Folds every envelope month by month through `through`,
+    /// applying the rollover policy in effect at each month end.
+    /// Alongside each envelope month is the sum returned to the pool at those month ends.
+    fn envelope_months(
+        &self,
+        through: Month,
+    ) -> BTreeMap<account::Name, BTreeMap<unit::Name, (EnvelopeMonth, Amount)>> {
+        let policies = self.all_rollover_policies();
+        let allocated = self.all_allocations().map(|allocation| {
+            (
+                allocation.envelope,
+                allocation.unit,
+                allocation.month,
+                (allocation.amount, Amount::default()),
+            )
+        });
+        let activity = self.all_envelope_activity().into_iter().map(|activity| {
+            (
+                activity.envelope,
+                activity.unit,
+                Month::of(activity.date),
+                (Amount::default(), activity.amount),
+            )
+        });
+        allocated
+            .chain(activity)
+            .filter(|(_envelope, _unit, month, _amounts)| *month <= through)
+            .fold(
+                BTreeMap::<_, BTreeMap<Month, (Amount, Amount)>>::new(),
+                |mut months, (envelope, unit, month, (allocated, activity))| {
+                    let sums = months
+                        .entry((envelope, unit))
+                        .or_default()
+                        .entry(month)
+                        .or_default();
+                    sums.0 += allocated;
+                    sums.1 += activity;
+                    months
+                },
+            )
+            .into_iter()
+            .fold(
+                BTreeMap::new(),
+                |mut envelopes, ((envelope, unit), months)| {
+                    let envelope_policies = policies.get(&envelope);
+                    let mut month = *months.keys().next().unwrap();
+                    let mut envelope_month = EnvelopeMonth::default();
+                    let mut returned = Amount::default();
+                    loop {
+                        let (allocated, activity) = months.get(&month).copied().unwrap_or_default();
+                        envelope_month.allocated = allocated;
+                        envelope_month.activity = activity;
+                        envelope_month.available =
+                            envelope_month.carried_over + allocated + activity;
+                        if month == through {
+                            break;
+                        }
+                        let policy = envelope_policies
+                            .and_then(|policies| policies.range(..=Some(month)).next_back())
+                            .map(|(_month, policy)| *policy)
+                            .unwrap_or_default();
+                        let carried_over = policy.carry_over(envelope_month.available);
+                        returned += envelope_month.available - carried_over;
+                        envelope_month = EnvelopeMonth {
+                            carried_over,
+                            ..Default::default()
+                        };
+                        month = month.succ();
+                    }
+                    envelopes
+                        .entry(envelope)
+                        .or_insert_with(BTreeMap::new)
+                        .insert(unit, (envelope_month, returned));
+                    envelopes
+                },
+            )
+    }
+}
+
+/// Moves out of a budget account are charged to it as an envelope,
+/// and moves between two budget accounts also credit the receiving one.
+/// Moves into a budget account from any other account are income,
+/// which is not charged to any envelope but is pooled instead.
+fn envelope_changes(
+    accounts: &BTreeMap<account::Name, Account>,
+    move_: &Move,
+) -> Vec<(account::Name, Amount)> {
+    let debit_is_envelope = is_budget(accounts, &move_.debit_account);
+    let credit_is_envelope = is_budget(accounts, &move_.credit_account);
+    let debit =
+        debit_is_envelope.then(|| (move_.debit_account.clone(), -Amount::from(move_.amount)));
+    let credit = (debit_is_envelope && credit_is_envelope)
+        .then(|| (move_.credit_account.clone(), Amount::from(move_.amount)));
+    debit.into_iter().chain(credit).collect()
+}
+
+fn is_budget(accounts: &BTreeMap<account::Name, Account>, account_name: &account::Name) -> bool {
+    matches!(
+        accounts.get(account_name),
+        Some(Account {
+            kind: account::Kind::Budget,
+            ..
+        })
+    )
+}
+
+#[test]
+fn allocating_ahead_keeps_the_current_month() {
+    let string = r#"[
+        AccountCreated((name:("a"),kind:Budget)),
+        AccountCreated((name:("b"),kind:External)),
+        UnitCreated((name:("USD"),decimal_places:2)),
+        RolloverPolicySet((envelope:("a"),policy:Reset)),
+        TransactionRecorded((date:"2022-09-01")),
+        MoveAdded((transaction:(1),debit_account:("b"),credit_account:("a"),amount:("500.00"),unit:("USD"))),
+        FundsAllocated((envelope:("a"),amount:("100.00"),unit:("USD"),month:(year:2022,month:9))),
+        FundsAllocated((envelope:("a"),amount:("10.00"),unit:("USD"),month:(year:2022,month:10))),
+    ]"#;
+    let events = Events::try_from_reader(&mut string.as_bytes()).unwrap();
+    assert_eq!(
+        events.current_month(),
+        Some(Month {
+            year: 2022,
+            month: 9
+        })
+    );
+    let usd = unit::Name("USD".into());
+    assert_eq!(events.to_be_budgeted()[&usd], "390.00".parse().unwrap());
+}
+#[test]
+fn rollover_policy_from_the_month_set() {
+    let string = r#"[
+        AccountCreated((name:("a"),kind:Budget)),
+        AccountCreated((name:("b"),kind:External)),
+        UnitCreated((name:("USD"),decimal_places:2)),
+        TransactionRecorded((date:"2022-09-01")),
+        MoveAdded((transaction:(1),debit_account:("b"),credit_account:("a"),amount:("500.00"),unit:("USD"))),
+        FundsAllocated((envelope:("a"),amount:("100.00"),unit:("USD"),month:(year:2022,month:9))),
+        TransactionRecorded((date:"2022-10-01")),
+        MoveAdded((transaction:(2),debit_account:("a"),credit_account:("b"),amount:("10.00"),unit:("USD"))),
+        RolloverPolicySet((envelope:("a"),policy:Reset)),
+    ]"#;
+    let mut events = Events::try_from_reader(&mut string.as_bytes()).unwrap();
+    let a = account::Name("a".into());
+    let usd = unit::Name("USD".into());
+    let october = Month {
+        year: 2022,
+        month: 10,
+    };
+    // September ended before the policy was set
+    let budget_month = events.budget_month(october);
+    assert_eq!(
+        budget_month[&a][&usd].carried_over,
+        "100.00".parse().unwrap()
+    );
+    assert_eq!(events.to_be_budgeted()[&usd], "400.00".parse().unwrap());
+    let string = r#"[
+        TransactionRecorded((date:"2022-11-01")),
+        MoveAdded((transaction:(3),debit_account:("b"),credit_account:("a"),amount:("1.00"),unit:("USD"))),
+    ]"#;
+    for record in ron::from_str::<Vec<Event>>(string).unwrap() {
+        events.try_push(record.into()).unwrap();
+    }
+    let budget_month = events.budget_month(october.succ());
+    assert_eq!(budget_month[&a][&usd].carried_over, Amount::default());
+    assert_eq!(events.to_be_budgeted()[&usd], "491.00".parse().unwrap());
 }